@@ -9,6 +9,9 @@ pub struct BoundingBox {
 }
 
 impl BoundingBox {
+    /// Tolerance for floating point error when deciding whether two boxes are touching.
+    pub const EPSILON: f32 = 1e-4;
+
     pub fn new(min: Vec3, max: Vec3) -> Self {
         Self { min, max }
     }
@@ -25,6 +28,68 @@ impl BoundingBox {
         let intersects_z = self.min.z < other.max.z && self.max.z > other.min.z;
         intersects_x && intersects_y && intersects_z
     }
+
+    /// Grows the box by `amount` in every direction.
+    pub fn expand_by(&self, amount: f32) -> Self {
+        Self {
+            min: self.min - amount,
+            max: self.max + amount,
+        }
+    }
+
+    /// Grows the box in the direction of `offset`, so that it covers every position the box
+    /// passes through while moving by `offset`.
+    pub fn expand_towards(&self, offset: Vec3) -> Self {
+        Self {
+            min: self.min + offset.min(Vec3::ZERO),
+            max: self.max + offset.max(Vec3::ZERO),
+        }
+    }
+
+    /// Limits a movement of `offset` along `axis` so that this box stops when it touches
+    /// `other`, rather than passing into or through it.
+    ///
+    /// Boxes that only touch or overlap by less than [`Self::EPSILON`] on the other axes are
+    /// ignored, so that sliding along a flat wall doesn't catch on the seams between blocks.
+    /// Boxes that already overlap along `axis` are also ignored, so that something stuck inside
+    /// a block can still move out of it.
+    pub fn clip_offset(&self, other: &BoundingBox, axis: usize, offset: f32) -> f32 {
+        let overlaps_other_axes = (0..3).filter(|&i| i != axis).all(|i| {
+            self.min[i] < other.max[i] - Self::EPSILON && self.max[i] > other.min[i] + Self::EPSILON
+        });
+        if !overlaps_other_axes {
+            return offset;
+        }
+
+        if offset > 0.0 && self.max[axis] <= other.min[axis] + Self::EPSILON {
+            offset.min(other.min[axis] - self.max[axis]).max(0.0)
+        } else if offset < 0.0 && self.min[axis] >= other.max[axis] - Self::EPSILON {
+            offset.max(other.max[axis] - self.min[axis]).min(0.0)
+        } else {
+            offset
+        }
+    }
+
+    /// Works out how far this box can move towards `displacement` without passing into any of
+    /// `obstacles`. Each axis is swept separately (vertical first), so the result slides along
+    /// walls and floors instead of stopping dead.
+    pub fn sweep(&self, displacement: Vec3, obstacles: &[BoundingBox]) -> Vec3 {
+        let mut bounding_box = *self;
+        let mut moved = Vec3::ZERO;
+        for axis in [1, 0, 2] {
+            let offset = obstacles
+                .iter()
+                .fold(displacement[axis], |offset, obstacle| {
+                    bounding_box.clip_offset(obstacle, axis, offset)
+                });
+
+            let mut axis_offset = Vec3::ZERO;
+            axis_offset[axis] = offset;
+            bounding_box = bounding_box + axis_offset;
+            moved[axis] = offset;
+        }
+        moved
+    }
 }
 
 impl Add<Vec3> for BoundingBox {
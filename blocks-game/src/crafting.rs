@@ -0,0 +1,522 @@
+use crate::{
+    inventory::{Inventory, ItemStack},
+    item::Item,
+    parse_error::ParseError,
+};
+
+/// The recipes that come with the game, in the format of a recipes file.
+pub const BUILTIN_RECIPES: &str = include_str!("../../assets/recipes.txt");
+/// Width and height of the crafting grid.
+pub const GRID_SIZE: usize = 3;
+/// Marks an empty slot in the pattern of a shaped recipe.
+const EMPTY_SYMBOL: char = '.';
+
+/// What has to be in the crafting grid for a recipe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ingredients {
+    /// Items laid out in a pattern, which can be anywhere in the grid and mirrored from left to
+    /// right. The pattern is stored row by row from the top.
+    Shaped {
+        width: usize,
+        height: usize,
+        pattern: Vec<Option<Item>>,
+    },
+    /// Items that can be anywhere in the grid.
+    Shapeless(Vec<Item>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recipe {
+    pub ingredients: Ingredients,
+    pub result: ItemStack,
+}
+
+impl Recipe {
+    /// Whether the grid holds exactly the ingredients of this recipe, ignoring stack sizes.
+    pub fn matches(&self, grid: &CraftingGrid) -> bool {
+        match &self.ingredients {
+            Ingredients::Shaped {
+                width,
+                height,
+                pattern,
+            } => {
+                let (grid_width, grid_height, items) = trim(GRID_SIZE, &grid.items());
+                if (grid_width, grid_height) != (*width, *height) {
+                    return false;
+                }
+                let mirrored = items
+                    .chunks(grid_width)
+                    .flat_map(|row| row.iter().rev().copied());
+                items == *pattern || mirrored.eq(pattern.iter().copied())
+            }
+            Ingredients::Shapeless(ingredients) => {
+                let mut remaining = ingredients.clone();
+                for item in grid.items().into_iter().flatten() {
+                    let Some(i) = remaining.iter().position(|&needed| needed == item) else {
+                        return false;
+                    };
+                    remaining.swap_remove(i);
+                }
+                remaining.is_empty()
+            }
+        }
+    }
+}
+
+/// Cuts the empty rows and columns from around a pattern of `width` columns. Returns the new
+/// width, height and pattern.
+fn trim(width: usize, pattern: &[Option<Item>]) -> (usize, usize, Vec<Option<Item>>) {
+    let filled = |i: usize| pattern[i].is_some();
+    let columns: Vec<_> = (0..width)
+        .filter(|&x| (x..pattern.len()).step_by(width).any(filled))
+        .collect();
+    let rows: Vec<_> = (0..pattern.len() / width)
+        .filter(|&y| (y * width..(y + 1) * width).any(filled))
+        .collect();
+    let (Some(&left), Some(&right), Some(&top), Some(&bottom)) =
+        (columns.first(), columns.last(), rows.first(), rows.last())
+    else {
+        return (0, 0, Vec::new());
+    };
+
+    let trimmed = (top..=bottom)
+        .flat_map(|y| (left..=right).map(move |x| pattern[y * width + x]))
+        .collect();
+    (right - left + 1, bottom - top + 1, trimmed)
+}
+
+fn parse_item(line: usize, name: &str) -> Result<Item, ParseError> {
+    Item::from_name(name).ok_or_else(|| ParseError::new(line, format!("unknown item `{name}`")))
+}
+
+/// Parses one recipe from its lines, each paired with its line number.
+fn parse_recipe(lines: &[(usize, &str)]) -> Result<Recipe, ParseError> {
+    let (header_line, header) = lines[0];
+    let mut words = header.split_whitespace();
+    let kind = words.next().unwrap_or_default();
+    let result = parse_item(
+        header_line,
+        words
+            .next()
+            .ok_or_else(|| ParseError::new(header_line, "missing the item the recipe makes"))?,
+    )?;
+    let count = match words.next() {
+        Some(count) => count
+            .parse()
+            .ok()
+            .filter(|&count| count > 0)
+            .ok_or_else(|| ParseError::new(header_line, format!("invalid count `{count}`")))?,
+        None => 1,
+    };
+    let result = ItemStack::new(result, count);
+
+    let ingredients = match kind {
+        "shaped" => parse_shaped(header_line, &lines[1..])?,
+        "shapeless" => {
+            let [(line, ingredients)] = lines[1..] else {
+                return Err(ParseError::new(
+                    header_line,
+                    "a shapeless recipe needs exactly one line of ingredients",
+                ));
+            };
+            let items = ingredients
+                .split_whitespace()
+                .map(|name| parse_item(line, name))
+                .collect::<Result<Vec<_>, _>>()?;
+            if items.is_empty() || items.len() > GRID_SIZE * GRID_SIZE {
+                return Err(ParseError::new(line, "wrong number of ingredients"));
+            }
+            Ingredients::Shapeless(items)
+        }
+        _ => {
+            return Err(ParseError::new(
+                header_line,
+                format!("expected `shaped` or `shapeless`, found `{kind}`"),
+            ))
+        }
+    };
+    Ok(Recipe {
+        ingredients,
+        result,
+    })
+}
+
+fn parse_shaped(header_line: usize, lines: &[(usize, &str)]) -> Result<Ingredients, ParseError> {
+    let (rows, keys): (Vec<_>, Vec<_>) = lines.iter().partition(|(_, text)| !text.contains('='));
+
+    let mut symbols = Vec::new();
+    for &(line, text) in &keys {
+        let (symbol, name) = text.split_once('=').unwrap();
+        let mut chars = symbol.trim().chars();
+        let (Some(symbol), None) = (chars.next(), chars.next()) else {
+            return Err(ParseError::new(line, "a symbol must be a single character"));
+        };
+        symbols.push((symbol, parse_item(line, name.trim())?));
+    }
+
+    let width = rows.first().map_or(0, |(_, row)| row.chars().count());
+    if rows.is_empty() || rows.len() > GRID_SIZE || width > GRID_SIZE {
+        return Err(ParseError::new(
+            header_line,
+            format!("a pattern must be between 1x1 and {GRID_SIZE}x{GRID_SIZE}"),
+        ));
+    }
+
+    let mut pattern = Vec::new();
+    for &(line, row) in &rows {
+        if row.chars().count() != width {
+            return Err(ParseError::new(line, "every row must be the same width"));
+        }
+        for symbol in row.chars() {
+            if symbol == EMPTY_SYMBOL {
+                pattern.push(None);
+                continue;
+            }
+            let Some(&(_, item)) = symbols.iter().find(|(s, _)| *s == symbol) else {
+                return Err(ParseError::new(line, format!("unknown symbol `{symbol}`")));
+            };
+            pattern.push(Some(item));
+        }
+    }
+
+    let (width, height, pattern) = trim(width, &pattern);
+    if pattern.is_empty() {
+        return Err(ParseError::new(header_line, "the pattern is empty"));
+    }
+    Ok(Ingredients::Shaped {
+        width,
+        height,
+        pattern,
+    })
+}
+
+/// Every recipe that can be crafted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recipes {
+    recipes: Vec<Recipe>,
+}
+
+impl Recipes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The recipes that come with the game.
+    pub fn builtin() -> Self {
+        let mut recipes = Self::new();
+        recipes
+            .load(BUILTIN_RECIPES)
+            .expect("built-in recipes should be valid");
+        recipes
+    }
+
+    /// Parses a recipes file, in the format of `assets/recipes.txt`.
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let mut recipes = Self::new();
+        recipes.load(source)?;
+        Ok(recipes)
+    }
+
+    /// Adds every recipe in `source`, which is in the format of `assets/recipes.txt`. Adds none
+    /// of them if any are invalid.
+    pub fn load(&mut self, source: &str) -> Result<(), ParseError> {
+        let mut recipes = Vec::new();
+        let mut lines = Vec::new();
+        let numbered = source.lines().map(str::trim).enumerate();
+        for (i, text) in numbered.chain([(usize::MAX, "")]) {
+            if text.starts_with('#') {
+                continue;
+            }
+            if !text.is_empty() {
+                lines.push((i + 1, text));
+            } else if !lines.is_empty() {
+                recipes.push(parse_recipe(&lines)?);
+                lines.clear();
+            }
+        }
+
+        self.recipes.extend(recipes);
+        Ok(())
+    }
+
+    pub fn add(&mut self, recipe: Recipe) {
+        self.recipes.push(recipe);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Recipe> {
+        self.recipes.iter()
+    }
+
+    /// The first recipe that `grid` holds the ingredients for.
+    pub fn find(&self, grid: &CraftingGrid) -> Option<&Recipe> {
+        self.recipes.iter().find(|recipe| recipe.matches(grid))
+    }
+}
+
+/// The grid that ingredients are laid out in to craft things.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CraftingGrid {
+    /// Row by row from the top left.
+    pub slots: [Option<ItemStack>; GRID_SIZE * GRID_SIZE],
+}
+
+impl CraftingGrid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn items(&self) -> [Option<Item>; GRID_SIZE * GRID_SIZE] {
+        self.slots.map(|slot| slot.map(|stack| stack.item))
+    }
+
+    /// What would be crafted from the grid as it is.
+    pub fn result(&self, recipes: &Recipes) -> Option<ItemStack> {
+        recipes.find(self).map(|recipe| recipe.result)
+    }
+
+    /// Crafts once from the grid, using up one item from every filled slot. Returns what was
+    /// made, or `None` if the grid doesn't match a recipe.
+    pub fn craft(&mut self, recipes: &Recipes) -> Option<ItemStack> {
+        let result = self.result(recipes)?;
+        for slot in &mut self.slots {
+            if let Some(stack) = slot {
+                stack.count -= 1;
+                if stack.count == 0 {
+                    *slot = None;
+                }
+            }
+        }
+        Some(result)
+    }
+
+    /// Empties the grid, returning everything that was in it.
+    pub fn take_all(&mut self) -> Vec<ItemStack> {
+        self.slots.iter_mut().filter_map(Option::take).collect()
+    }
+}
+
+/// A slot on the crafting screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slot {
+    Inventory(usize),
+    Grid(usize),
+    /// Where the crafted item appears.
+    Result,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Click {
+    /// Picks up, puts down or swaps whole stacks.
+    Stack,
+    /// Picks up half a stack or puts down a single item.
+    Single,
+}
+
+/// The crafting grid as the player is using it, with the stack they are moving between slots.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CraftingScreen {
+    pub grid: CraftingGrid,
+    /// The stack picked up and being carried to another slot.
+    pub held: Option<ItemStack>,
+}
+
+impl CraftingScreen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn click(
+        &mut self,
+        slot: Slot,
+        click: Click,
+        inventory: &mut Inventory,
+        recipes: &Recipes,
+    ) {
+        match slot {
+            Slot::Inventory(i) if inventory.infinite => {
+                // The creative palette never runs out, so picking a stack up copies it and
+                // putting one down gets rid of it.
+                if self.held.take().is_none() {
+                    self.held = inventory.slots.get(i).copied().flatten();
+                }
+            }
+            Slot::Inventory(i) => {
+                if let Some(slot) = inventory.slots.get_mut(i) {
+                    click_slot(slot, &mut self.held, click);
+                }
+            }
+            Slot::Grid(i) => {
+                if let Some(slot) = self.grid.slots.get_mut(i) {
+                    click_slot(slot, &mut self.held, click);
+                }
+            }
+            Slot::Result => {
+                let Some(result) = self.grid.result(recipes) else {
+                    return;
+                };
+                let fits = self.held.is_none_or(|held| {
+                    held.item == result.item
+                        && held.count + result.count <= result.item.max_stack_size()
+                });
+                if fits {
+                    self.grid.craft(recipes);
+                    let held = self.held.get_or_insert(ItemStack::new(result.item, 0));
+                    held.count += result.count;
+                }
+            }
+        }
+    }
+
+    /// Empties the screen, returning everything in the grid and the held stack.
+    pub fn take_all(&mut self) -> Vec<ItemStack> {
+        let mut stacks = self.grid.take_all();
+        stacks.extend(self.held.take());
+        stacks
+    }
+}
+
+/// Moves items between `slot` and the held stack.
+fn click_slot(slot: &mut Option<ItemStack>, held: &mut Option<ItemStack>, click: Click) {
+    match (slot.as_mut(), held.as_mut(), click) {
+        (None, None, _) => {}
+        (Some(_), None, Click::Stack) => *held = slot.take(),
+        (Some(stack), None, Click::Single) => {
+            let taken = stack.count.div_ceil(2);
+            stack.count -= taken;
+            *held = Some(ItemStack::new(stack.item, taken));
+            if stack.count == 0 {
+                *slot = None;
+            }
+        }
+        (None, Some(_), Click::Stack) => *slot = held.take(),
+        (None, Some(carried), Click::Single) => {
+            carried.count -= 1;
+            *slot = Some(ItemStack::new(carried.item, 1));
+            if carried.count == 0 {
+                *held = None;
+            }
+        }
+        (Some(stack), Some(carried), _) if stack.item == carried.item => {
+            let room = stack.item.max_stack_size().saturating_sub(stack.count);
+            let moved = match click {
+                Click::Stack => carried.count.min(room),
+                Click::Single => room.min(1),
+            };
+            stack.count += moved;
+            carried.count -= moved;
+            if carried.count == 0 {
+                *held = None;
+            }
+        }
+        (Some(_), Some(_), _) => std::mem::swap(slot, held),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Click, CraftingGrid, CraftingScreen, Recipes, Slot};
+    use crate::{
+        inventory::{Inventory, ItemStack},
+        item::{Item, ToolKind, ToolTier},
+        terrain::block::Block,
+    };
+
+    const STONE: Item = Item::Block(Block::STONE);
+    const STONE_PICKAXE: Item = Item::Tool(ToolKind::Pickaxe, ToolTier::Stone);
+
+    fn grid_of(items: [Option<Item>; 9]) -> CraftingGrid {
+        CraftingGrid {
+            slots: items.map(|item| item.map(|item| ItemStack::new(item, 1))),
+        }
+    }
+
+    #[test]
+    fn builtin_recipes_are_valid() {
+        assert!(Recipes::builtin().iter().count() > 0);
+    }
+
+    #[test]
+    fn shaped_recipes_match_anywhere_in_the_grid() {
+        let mut recipes = Recipes::new();
+        recipes.load("shaped bread 2\nA.\n.A\nA = apple\n").unwrap();
+        let a = Some(Item::Apple);
+
+        let top_left = grid_of([a, None, None, None, a, None, None, None, None]);
+        let bottom_right = grid_of([None, None, None, None, a, None, None, None, a]);
+        let mirrored = grid_of([None, a, None, a, None, None, None, None, None]);
+        let wrong = grid_of([a, None, None, None, None, None, None, None, a]);
+
+        let bread = Some(ItemStack::new(Item::Bread, 2));
+        assert_eq!(top_left.result(&recipes), bread);
+        assert_eq!(bottom_right.result(&recipes), bread);
+        assert_eq!(mirrored.result(&recipes), bread);
+        assert_eq!(wrong.result(&recipes), None);
+    }
+
+    #[test]
+    fn shapeless_recipes_match_in_any_order() {
+        let mut recipes = Recipes::new();
+        recipes.load("shapeless bread\napple apple bone").unwrap();
+        let (a, b) = (Some(Item::Apple), Some(Item::Bone));
+
+        let grid = grid_of([None, a, None, b, None, None, None, None, a]);
+        assert_eq!(grid.result(&recipes), Some(ItemStack::new(Item::Bread, 1)));
+
+        let missing = grid_of([None, a, None, b, None, None, None, None, None]);
+        let extra = grid_of([a, a, None, b, None, None, None, None, a]);
+        assert_eq!(missing.result(&recipes), None);
+        assert_eq!(extra.result(&recipes), None);
+    }
+
+    #[test]
+    fn invalid_recipes_report_their_line() {
+        let mut recipes = Recipes::new();
+
+        let error = recipes
+            .load("# comment\nshapeless bread\napple\n\nshaped bread\nAA\nA = carrot\n")
+            .unwrap_err();
+
+        assert_eq!(error.line, 7);
+        assert_eq!(recipes.iter().count(), 0);
+    }
+
+    #[test]
+    fn crafting_uses_up_one_of_each_ingredient() {
+        let recipes = Recipes::builtin();
+        let (s, b) = (Some(STONE), Some(Item::Bone));
+        let mut grid = grid_of([s, s, s, None, b, None, None, b, None]);
+        grid.slots[0] = Some(ItemStack::new(STONE, 3));
+
+        assert_eq!(grid.craft(&recipes), Some(ItemStack::new(STONE_PICKAXE, 1)));
+
+        assert_eq!(grid.slots[0], Some(ItemStack::new(STONE, 2)));
+        assert_eq!(grid.slots[1], None);
+        assert_eq!(grid.craft(&recipes), None);
+    }
+
+    #[test]
+    fn clicking_moves_stacks_around() {
+        let recipes = Recipes::builtin();
+        let mut inventory = Inventory::new();
+        inventory.slots[0] = Some(ItemStack::new(Item::Block(Block::DIRT), 3));
+        inventory.slots[1] = Some(ItemStack::new(Item::Bone, 1));
+        let mut screen = CraftingScreen::new();
+
+        screen.click(Slot::Inventory(0), Click::Stack, &mut inventory, &recipes);
+        screen.click(Slot::Grid(4), Click::Single, &mut inventory, &recipes);
+        screen.click(Slot::Inventory(0), Click::Stack, &mut inventory, &recipes);
+        screen.click(Slot::Inventory(1), Click::Stack, &mut inventory, &recipes);
+        screen.click(Slot::Grid(0), Click::Stack, &mut inventory, &recipes);
+        screen.click(Slot::Result, Click::Stack, &mut inventory, &recipes);
+
+        assert_eq!(
+            screen.held,
+            Some(ItemStack::new(Item::Block(Block::GRASS), 1))
+        );
+        assert_eq!(
+            inventory.slots[0],
+            Some(ItemStack::new(Item::Block(Block::DIRT), 2))
+        );
+        assert_eq!(screen.grid, CraftingGrid::new());
+    }
+}
@@ -0,0 +1,160 @@
+use glam::{vec3, Vec3};
+
+use super::{Entities, Entity, EntityKind};
+use crate::{bounding_box::BoundingBox, inventory::ItemStack, physics::Body};
+
+const SIZE: f32 = 0.25;
+/// Upwards speed of items popping out of a broken block.
+const POP_SPEED: f32 = 4.0;
+/// How quickly items slide to a stop on the ground, per second.
+const GROUND_FRICTION: f32 = 8.0;
+/// Items of the same kind closer than this combine into one stack.
+const MERGE_DISTANCE: f32 = 0.75;
+/// How far outside the player's bounding box items are picked up from.
+pub const PICKUP_RANGE: f32 = 1.0;
+/// Seconds before a new item can be picked up, so it is seen popping out of its block.
+const PICKUP_DELAY: f32 = 0.5;
+/// Seconds before an item that hasn't been picked up disappears.
+const DESPAWN_TIME: f32 = 300.0;
+const BOB_HEIGHT: f32 = 0.1;
+/// Radians per second.
+const BOB_SPEED: f32 = 2.5;
+
+/// A stack of items lying in the world.
+#[derive(Debug, Clone)]
+pub struct DroppedItem {
+    pub stack: ItemStack,
+}
+
+impl DroppedItem {
+    /// An entity for `stack` lying at `position`.
+    pub fn entity(stack: ItemStack, position: Vec3) -> Entity {
+        let mut body = Body::new(position, SIZE, SIZE);
+        body.ground_friction = GROUND_FRICTION;
+        Entity::new(body, EntityKind::Item(Self { stack }))
+    }
+
+    /// Drops `stack` out of a block that has just been broken.
+    pub fn from_block(stack: ItemStack, block_position: Vec3) -> Entity {
+        let mut entity = Self::entity(stack, block_position + vec3(0.5, 0.5 - SIZE / 2.0, 0.5));
+        entity.body.velocity.y = POP_SPEED;
+        entity
+    }
+
+    pub fn is_expired(&self, age: f32) -> bool {
+        age >= DESPAWN_TIME || self.stack.count == 0
+    }
+}
+
+/// How far an item of the given age is drawn above its position, so it bobs up and down.
+pub fn bob_offset(age: f32) -> f32 {
+    BOB_HEIGHT * (1.0 + (age * BOB_SPEED).sin())
+}
+
+pub fn can_be_picked_up(age: f32) -> bool {
+    age >= PICKUP_DELAY
+}
+
+/// Combines nearby stacks of the same item into the older entity, leaving empty stacks behind to
+/// be removed.
+pub fn merge_nearby(entities: &mut Entities) {
+    let ids: Vec<_> = entities
+        .iter()
+        .filter(|(_, entity)| entity.as_item().is_some())
+        .map(|(id, _)| id)
+        .collect();
+
+    for id in ids {
+        let into = entities.get(id).unwrap();
+        let position = into.body.position;
+        let item = into.as_item().unwrap().stack.item;
+
+        let area = BoundingBox::new(
+            position - Vec3::splat(MERGE_DISTANCE),
+            position + Vec3::splat(MERGE_DISTANCE),
+        );
+        let nearby: Vec<_> = entities
+            .intersecting(area)
+            .filter(|&(other, entity)| {
+                other > id
+                    && entity.body.position.distance(position) <= MERGE_DISTANCE
+                    && entity.as_item().is_some_and(|d| d.stack.item == item)
+            })
+            .map(|(other, _)| other)
+            .collect();
+
+        for other in nearby {
+            let into = entities.get(id).unwrap().as_item().unwrap().stack;
+            let from = entities.get(other).unwrap().as_item().unwrap().stack;
+            let moved = (item.max_stack_size() - into.count).min(from.count);
+
+            let into = entities.get_mut(id).and_then(Entity::as_item_mut).unwrap();
+            into.stack.count += moved;
+            let from = entities
+                .get_mut(other)
+                .and_then(Entity::as_item_mut)
+                .unwrap();
+            from.stack.count -= moved;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::vec3;
+
+    use super::{merge_nearby, DroppedItem, DESPAWN_TIME};
+    use crate::{
+        entity::Entities,
+        inventory::ItemStack,
+        item::Item,
+        terrain::{block::Block, Terrain},
+    };
+
+    const DIRT: Item = Item::Block(Block::DIRT);
+
+    #[test]
+    fn falls_onto_the_ground() {
+        let terrain = Terrain::with_floor(0..=0, 0..=0, 10, Block::STONE);
+        let mut item = DroppedItem::from_block(ItemStack::new(DIRT, 1), vec3(4.0, 11.0, 4.0));
+
+        for _ in 0..100 {
+            item.update(0.03, &terrain);
+        }
+
+        assert!(item.body.on_ground);
+        assert_eq!(item.body.position.y, 11.0);
+    }
+
+    #[test]
+    fn merges_with_nearby_stacks() {
+        let mut entities = Entities::new();
+        for (stack, position) in [
+            (ItemStack::new(DIRT, 60), vec3(0.0, 0.0, 0.0)),
+            (ItemStack::new(DIRT, 10), vec3(0.5, 0.0, 0.0)),
+            (ItemStack::new(DIRT, 1), vec3(5.0, 0.0, 0.0)),
+            (ItemStack::new(Item::Apple, 1), vec3(0.0, 0.0, 0.5)),
+        ] {
+            entities.spawn(DroppedItem::entity(stack, position));
+        }
+
+        merge_nearby(&mut entities);
+
+        let counts: Vec<_> = entities
+            .iter()
+            .map(|(_, entity)| entity.as_item().unwrap().stack.count)
+            .collect();
+        assert_eq!(counts, [64, 6, 1, 1]);
+    }
+
+    #[test]
+    fn expires_eventually() {
+        let terrain = Terrain::with_floor(0..=0, 0..=0, 10, Block::STONE);
+        let mut item = DroppedItem::entity(ItemStack::new(DIRT, 1), vec3(4.0, 11.0, 4.0));
+
+        item.update(DESPAWN_TIME / 2.0, &terrain);
+        assert!(!item.is_expired());
+        item.update(DESPAWN_TIME / 2.0, &terrain);
+        assert!(item.is_expired());
+    }
+}
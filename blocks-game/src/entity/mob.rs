@@ -0,0 +1,454 @@
+use std::f32::consts::TAU;
+
+use glam::{ivec3, vec3, IVec3, Vec3, Vec3Swizzles};
+
+use super::{Entities, Entity, EntityId, EntityKind};
+use crate::{
+    inventory::ItemStack,
+    item::Item,
+    pathfinding::{find_path, PathFollower, PathOptions},
+    physics::{self, Body},
+    player::Player,
+    terrain::{block::Block, generation::random::Random, Terrain},
+};
+
+/// Upwards speed of a jump, which clears one block.
+const JUMP_SPEED: f32 = 7.5;
+const GROUND_FRICTION: f32 = 10.0;
+/// How far mobs walk from where they are in one go when wandering around.
+const WANDER_DISTANCE: i32 = 8;
+/// Seconds spent standing around between walks.
+const MIN_IDLE_TIME: f32 = 2.0;
+const MAX_IDLE_TIME: f32 = 6.0;
+/// Seconds a mob keeps trying to follow a path per waypoint before giving up, in case it gets
+/// stuck.
+const TIME_PER_WAYPOINT: f32 = 2.0;
+/// How close the player has to be for hostile mobs to notice them.
+const CHASE_DISTANCE: f32 = 16.0;
+/// Seconds between recalculating the path to a moving target.
+const REPATH_INTERVAL: f32 = 1.0;
+/// Positions searched per path, so that many mobs can think in the same tick.
+const PATH_BUDGET: usize = 400;
+/// How far outside a mob's bounding box it can hit the player from.
+const ATTACK_RANGE: f32 = 0.5;
+const ATTACK_COOLDOWN: f32 = 1.0;
+/// Seconds after being hit that a mob is knocked back rather than walking, and drawn red.
+pub const HURT_TIME: f32 = 0.5;
+const KNOCKBACK_SPEED: f32 = 6.0;
+const KNOCKBACK_LIFT: f32 = 5.0;
+
+/// Mobs never spawn closer to the player than this, so they don't appear out of thin air.
+const MIN_SPAWN_DISTANCE: f32 = 24.0;
+const MAX_SPAWN_DISTANCE: f32 = 48.0;
+/// Mobs this far from the player are removed.
+pub const DESPAWN_DISTANCE: f32 = 56.0;
+/// The most mobs of each kind that can be around at once.
+const MAX_MOBS_PER_KIND: usize = 8;
+/// Hostile mobs only spawn in sky light at or below this level.
+const MAX_HOSTILE_LIGHT: u8 = 7;
+/// Passive mobs only spawn in sky light at or above this level.
+const MIN_PASSIVE_LIGHT: u8 = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MobKind {
+    Pig,
+    Zombie,
+}
+
+impl MobKind {
+    pub const ALL: [Self; 2] = [Self::Pig, Self::Zombie];
+
+    /// Size of the bounding box along x and z.
+    pub fn width(self) -> f32 {
+        match self {
+            Self::Pig => 1.2,
+            Self::Zombie => 1.5,
+        }
+    }
+
+    pub fn height(self) -> f32 {
+        match self {
+            Self::Pig => 1.2,
+            Self::Zombie => 2.7,
+        }
+    }
+
+    pub fn max_health(self) -> f32 {
+        match self {
+            Self::Pig => 10.0,
+            Self::Zombie => 20.0,
+        }
+    }
+
+    /// Walking speed in blocks per second.
+    pub fn speed(self) -> f32 {
+        match self {
+            Self::Pig => 2.0,
+            Self::Zombie => 3.0,
+        }
+    }
+
+    /// Whether the mob chases and attacks the player.
+    pub fn is_hostile(self) -> bool {
+        match self {
+            Self::Pig => false,
+            Self::Zombie => true,
+        }
+    }
+
+    pub fn attack_damage(self) -> f32 {
+        match self {
+            Self::Pig => 0.0,
+            Self::Zombie => 3.0,
+        }
+    }
+
+    /// The item left behind when the mob dies, and the most of it that can drop.
+    pub fn drops(self) -> (Item, u32) {
+        match self {
+            Self::Pig => (Item::RawMeat, 3),
+            Self::Zombie => (Item::Bone, 2),
+        }
+    }
+
+    fn path_options(self) -> PathOptions {
+        PathOptions {
+            max_nodes: PATH_BUDGET,
+            ..PathOptions::new(self.width(), self.height())
+        }
+    }
+}
+
+/// A creature that walks around on its own.
+#[derive(Debug, Clone)]
+pub struct Mob {
+    pub kind: MobKind,
+    pub health: f32,
+    /// Which way the mob is facing, in radians clockwise from +z.
+    pub yaw: f32,
+    /// Seconds left of being knocked back after a hit.
+    pub hurt_time: f32,
+    follower: Option<PathFollower>,
+    /// Seconds left before giving up on the current path.
+    path_time: f32,
+    /// Seconds left to stand around before wandering off again.
+    idle_time: f32,
+    chasing: bool,
+    repath_time: f32,
+    attack_cooldown: f32,
+}
+
+impl Mob {
+    pub fn new(kind: MobKind) -> Self {
+        Self {
+            kind,
+            health: kind.max_health(),
+            yaw: 0.0,
+            hurt_time: 0.0,
+            follower: None,
+            path_time: 0.0,
+            idle_time: MIN_IDLE_TIME,
+            chasing: false,
+            repath_time: 0.0,
+            attack_cooldown: 0.0,
+        }
+    }
+
+    /// An entity for a new mob of `kind` standing at `position`.
+    pub fn entity(kind: MobKind, position: Vec3) -> Entity {
+        let mut body = Body::new(position, kind.width(), kind.height());
+        body.ground_friction = GROUND_FRICTION;
+        Entity::new(body, EntityKind::Mob(Self::new(kind)))
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0.0
+    }
+
+    /// Takes `amount` damage and knocks the mob away from `source`.
+    pub fn hurt(&mut self, body: &mut Body, amount: f32, source: Vec3) {
+        self.health -= amount;
+        self.hurt_time = HURT_TIME;
+
+        let away = (body.position - source).xz().normalize_or_zero();
+        body.velocity = vec3(away.x, 0.0, away.y) * KNOCKBACK_SPEED + KNOCKBACK_LIFT * Vec3::Y;
+    }
+
+    /// What the mob leaves behind when it dies.
+    pub fn drops(&self, random: &mut Random) -> ItemStack {
+        let (item, max_count) = self.kind.drops();
+        let count = 1 + random.next_int_bounded(max_count as i32) as u32;
+        ItemStack::new(item, count)
+    }
+
+    /// Decides where to go and steers `body` there. Returns how much damage the mob deals to the
+    /// player this tick.
+    pub fn update(
+        &mut self,
+        body: &mut Body,
+        delta_time: f32,
+        terrain: &Terrain,
+        player: &Player,
+        random: &mut Random,
+    ) -> f32 {
+        self.hurt_time = (self.hurt_time - delta_time).max(0.0);
+        self.attack_cooldown = (self.attack_cooldown - delta_time).max(0.0);
+        self.path_time -= delta_time;
+        if self.path_time <= 0.0 {
+            self.follower = None;
+        }
+
+        // Players passing through walls can't be reached, and spectators aren't really there.
+        let notices_player = self.kind.is_hostile()
+            && player.can_interact()
+            && player.movement_mode.collides()
+            && body.position.distance(player.position) < CHASE_DISTANCE;
+
+        let mut damage = 0.0;
+        if notices_player {
+            self.repath_time -= delta_time;
+            if !self.chasing || self.repath_time <= 0.0 {
+                self.walk_to(body, player.position.floor().as_ivec3(), terrain);
+                self.repath_time = REPATH_INTERVAL;
+            }
+            self.chasing = true;
+
+            let reach = body.bounding_box().expand_by(ATTACK_RANGE);
+            if self.attack_cooldown == 0.0 && reach.intersects(&player.bounding_box()) {
+                damage = self.kind.attack_damage();
+                self.attack_cooldown = ATTACK_COOLDOWN;
+            }
+        } else {
+            if self.chasing {
+                self.chasing = false;
+                self.follower = None;
+            }
+            if self.follower.is_none() {
+                self.idle_time -= delta_time;
+                if self.idle_time <= 0.0 {
+                    self.wander(body, terrain, random);
+                    self.idle_time =
+                        MIN_IDLE_TIME + random.next_float() * (MAX_IDLE_TIME - MIN_IDLE_TIME);
+                }
+            }
+        }
+
+        if self.hurt_time == 0.0 {
+            self.steer(body, terrain);
+        }
+        damage
+    }
+
+    fn wander(&mut self, body: &Body, terrain: &Terrain, random: &mut Random) {
+        let offset = ivec3(
+            random.next_int_bounded(2 * WANDER_DISTANCE + 1) - WANDER_DISTANCE,
+            0,
+            random.next_int_bounded(2 * WANDER_DISTANCE + 1) - WANDER_DISTANCE,
+        );
+        self.walk_to(body, body.position.floor().as_ivec3() + offset, terrain);
+    }
+
+    fn walk_to(&mut self, body: &Body, goal: IVec3, terrain: &Terrain) {
+        let start = body.position.floor().as_ivec3();
+        self.follower = find_path(terrain, start, goal, &self.kind.path_options())
+            .filter(|path| !path.waypoints.is_empty())
+            .map(PathFollower::new);
+        if let Some(follower) = &self.follower {
+            self.path_time = follower.path.waypoints.len() as f32 * TIME_PER_WAYPOINT;
+        }
+    }
+
+    fn steer(&mut self, body: &mut Body, terrain: &Terrain) {
+        let Some(follower) = &mut self.follower else {
+            return;
+        };
+        let Some(steering) = follower.steer(body, terrain) else {
+            self.follower = None;
+            return;
+        };
+
+        let velocity = steering.direction * self.kind.speed();
+        body.velocity.x = velocity.x;
+        body.velocity.z = velocity.z;
+        if steering.direction != Vec3::ZERO {
+            self.yaw = steering.direction.x.atan2(steering.direction.z);
+        }
+        if steering.jump {
+            body.velocity.y = JUMP_SPEED;
+        }
+    }
+}
+
+/// Tries to spawn a random mob somewhere around `center`, following the spawning rules for its
+/// kind, while the light level under open sky is `sky_light`. Returns the new mob, if one was
+/// spawned.
+pub fn spawn_random(
+    entities: &mut Entities,
+    terrain: &Terrain,
+    center: Vec3,
+    sky_light: u8,
+    random: &mut Random,
+) -> Option<EntityId> {
+    let kind = MobKind::ALL[random.next_int_bounded(MobKind::ALL.len() as i32) as usize];
+    let existing = entities
+        .iter()
+        .filter(|(_, entity)| entity.as_mob().is_some_and(|mob| mob.kind == kind))
+        .count();
+    if existing >= MAX_MOBS_PER_KIND {
+        return None;
+    }
+
+    let angle = random.next_float() * TAU;
+    let distance =
+        MIN_SPAWN_DISTANCE + random.next_float() * (MAX_SPAWN_DISTANCE - MIN_SPAWN_DISTANCE);
+    let column = (center + distance * vec3(angle.cos(), 0.0, angle.sin())).floor();
+    let surface = terrain.surface_height(column.x as i32, column.z as i32)?;
+
+    // Hostile mobs can spawn in caves as well as on the surface.
+    let start = if kind.is_hostile() {
+        1 + random.next_int_bounded(surface + 1)
+    } else {
+        surface + 1
+    };
+    let position = (1..=start)
+        .rev()
+        .map(|y| ivec3(column.x as i32, y, column.z as i32))
+        .find(|&p| can_spawn(kind, p, terrain, sky_light))?;
+
+    Some(entities.spawn(Mob::entity(kind, position.as_vec3() + vec3(0.5, 0.0, 0.5))))
+}
+
+/// Whether a mob of `kind` is allowed to spawn standing at `position`.
+fn can_spawn(kind: MobKind, position: IVec3, terrain: &Terrain, sky_light: u8) -> bool {
+    let floor = terrain.block(position - IVec3::Y);
+    if floor.collision_box(position - IVec3::Y).is_none() {
+        return false;
+    }
+
+    let entity = Mob::entity(kind, position.as_vec3() + vec3(0.5, 0.0, 0.5));
+    let bounding_box = entity.body.bounding_box();
+    if physics::collides(terrain, bounding_box) || physics::in_liquid(terrain, bounding_box) {
+        return false;
+    }
+
+    let light = terrain.sky_light(position).min(sky_light);
+    if kind.is_hostile() {
+        light <= MAX_HOSTILE_LIGHT
+    } else {
+        floor == Block::GRASS && light >= MIN_PASSIVE_LIGHT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{ivec3, vec3, IVec3, Vec3};
+
+    use super::{can_spawn, Mob, MobKind};
+    use crate::{
+        entity::{Entity, EntityKind},
+        player::{MovementMode, Player, MAX_HEALTH},
+        terrain::{block::Block, generation::random::Random, Terrain, MAX_LIGHT},
+    };
+
+    const FLOOR: i32 = 10;
+
+    fn terrain_with_floor(block: Block) -> Terrain {
+        Terrain::with_floor(0..=1, 0..=0, FLOOR, block)
+    }
+
+    fn player_at(position: Vec3) -> Player {
+        let mut player = Player::new();
+        player.position = position;
+        player.health = MAX_HEALTH;
+        player
+    }
+
+    fn run(mob: &mut Entity, player: &Player, terrain: &Terrain, ticks: u32) -> f32 {
+        let mut random = Random::with_seed(1);
+        let mut damage = 0.0;
+        for _ in 0..ticks {
+            let EntityKind::Mob(state) = &mut mob.kind else {
+                unreachable!();
+            };
+            damage += state.update(&mut mob.body, 1.0 / 60.0, terrain, player, &mut random);
+            mob.update(1.0 / 60.0, terrain);
+        }
+        damage
+    }
+
+    #[test]
+    fn hostile_mobs_chase_and_attack_the_player() {
+        let terrain = terrain_with_floor(Block::STONE);
+        let player = player_at(vec3(20.5, FLOOR as f32 + 1.0, 4.5));
+        let mut zombie = Mob::entity(MobKind::Zombie, vec3(10.5, FLOOR as f32 + 1.0, 4.5));
+
+        let damage = run(&mut zombie, &player, &terrain, 600);
+
+        assert!(zombie.body.position.distance(player.position) < 3.0);
+        assert!(damage > 0.0);
+    }
+
+    #[test]
+    fn hostile_mobs_ignore_spectators() {
+        let terrain = terrain_with_floor(Block::STONE);
+        let mut player = player_at(vec3(12.5, FLOOR as f32 + 1.0, 4.5));
+        player.movement_mode = MovementMode::Spectator;
+        let mut zombie = Mob::entity(MobKind::Zombie, vec3(10.5, FLOOR as f32 + 1.0, 4.5));
+
+        let damage = run(&mut zombie, &player, &terrain, 600);
+
+        assert_eq!(damage, 0.0);
+    }
+
+    #[test]
+    fn passive_mobs_wander_without_attacking() {
+        let terrain = terrain_with_floor(Block::GRASS);
+        let player = player_at(vec3(20.5, FLOOR as f32 + 1.0, 4.5));
+        let start = vec3(10.5, FLOOR as f32 + 1.0, 8.5);
+        let mut pig = Mob::entity(MobKind::Pig, start);
+
+        let damage = run(&mut pig, &player, &terrain, 1200);
+
+        assert_ne!(pig.body.position, start);
+        assert_eq!(damage, 0.0);
+    }
+
+    #[test]
+    fn spawning_depends_on_light_and_ground() {
+        let mut terrain = terrain_with_floor(Block::GRASS);
+        let open = ivec3(4, FLOOR + 1, 4);
+        assert!(can_spawn(MobKind::Pig, open, &terrain, MAX_LIGHT));
+        assert!(!can_spawn(MobKind::Zombie, open, &terrain, MAX_LIGHT));
+        assert!(!can_spawn(MobKind::Pig, open, &terrain, 0));
+        assert!(can_spawn(MobKind::Zombie, open, &terrain, 0));
+
+        for x in 0..16 {
+            for z in 0..16 {
+                terrain.set_block(ivec3(x, FLOOR + 5, z), Block::STONE);
+            }
+        }
+        assert!(!can_spawn(MobKind::Pig, open, &terrain, MAX_LIGHT));
+        assert!(can_spawn(MobKind::Zombie, open, &terrain, MAX_LIGHT));
+        assert!(!can_spawn(
+            MobKind::Zombie,
+            open + IVec3::Y,
+            &terrain,
+            MAX_LIGHT
+        ));
+    }
+
+    #[test]
+    fn being_hit_knocks_back_and_kills() {
+        let mut pig = Mob::entity(MobKind::Pig, Vec3::ZERO);
+        let EntityKind::Mob(mob) = &mut pig.kind else {
+            unreachable!();
+        };
+
+        mob.hurt(&mut pig.body, 6.0, vec3(-1.0, 0.0, 0.0));
+        assert!(pig.body.velocity.x > 0.0);
+        assert!(!mob.is_dead());
+
+        mob.hurt(&mut pig.body, 6.0, vec3(-1.0, 0.0, 0.0));
+        assert!(mob.is_dead());
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use glam::Vec3;
+
+use crate::{
+    bounding_box::BoundingBox, physics::Body, terrain::subchunk::Subchunk, terrain::Terrain,
+};
+
+use self::{item::DroppedItem, mob::Mob};
+
+pub mod item;
+pub mod mob;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EntityId(u64);
+
+/// Anything other than the player that lives in the world and moves around.
+#[derive(Debug, Clone)]
+pub struct Entity {
+    pub body: Body,
+    /// Where the entity was before the latest tick, so that it can be drawn moving smoothly.
+    pub previous_position: Vec3,
+    pub kind: EntityKind,
+    /// Seconds since the entity was spawned.
+    pub age: f32,
+}
+
+#[derive(Debug, Clone)]
+pub enum EntityKind {
+    Item(DroppedItem),
+    Mob(Mob),
+}
+
+impl Entity {
+    pub fn new(body: Body, kind: EntityKind) -> Self {
+        Self {
+            previous_position: body.position,
+            body,
+            kind,
+            age: 0.0,
+        }
+    }
+
+    pub fn as_item(&self) -> Option<&DroppedItem> {
+        match &self.kind {
+            EntityKind::Item(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn as_item_mut(&mut self) -> Option<&mut DroppedItem> {
+        match &mut self.kind {
+            EntityKind::Item(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn as_mob(&self) -> Option<&Mob> {
+        match &self.kind {
+            EntityKind::Mob(mob) => Some(mob),
+            _ => None,
+        }
+    }
+
+    /// The entity's position `alpha` of the way from the previous tick to the latest one.
+    pub fn interpolated_position(&self, alpha: f32) -> Vec3 {
+        self.previous_position.lerp(self.body.position, alpha)
+    }
+
+    pub fn update(&mut self, delta_time: f32, terrain: &Terrain) {
+        self.age += delta_time;
+        self.body.update(delta_time, terrain);
+    }
+
+    /// Whether the entity should be removed from the world.
+    pub fn is_expired(&self) -> bool {
+        match &self.kind {
+            EntityKind::Item(item) => item.is_expired(self.age),
+            EntityKind::Mob(mob) => mob.is_dead(),
+        }
+    }
+}
+
+/// The chunk column containing `position`.
+pub fn chunk_of(position: Vec3) -> (i32, i32) {
+    let block = position.floor().as_ivec3();
+    (
+        block.x.div_euclid(Subchunk::SIZE as i32),
+        block.z.div_euclid(Subchunk::SIZE as i32),
+    )
+}
+
+struct Slot {
+    entity: Entity,
+    /// The chunk the entity is filed under in `Entities::chunks`, which can lag behind its
+    /// position until `Entities::update_chunks` is called.
+    chunk: (i32, i32),
+}
+
+/// Every entity in the world, indexed by chunk so that nearby entities can be found quickly.
+#[derive(Default)]
+pub struct Entities {
+    next_id: u64,
+    slots: BTreeMap<EntityId, Slot>,
+    chunks: BTreeMap<(i32, i32), BTreeSet<EntityId>>,
+}
+
+impl Entities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn(&mut self, entity: Entity) -> EntityId {
+        let id = EntityId(self.next_id);
+        self.next_id += 1;
+
+        let chunk = chunk_of(entity.body.position);
+        self.chunks.entry(chunk).or_default().insert(id);
+        self.slots.insert(id, Slot { entity, chunk });
+        id
+    }
+
+    pub fn remove(&mut self, id: EntityId) -> Option<Entity> {
+        let slot = self.slots.remove(&id)?;
+        self.unfile(id, slot.chunk);
+        Some(slot.entity)
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<&Entity> {
+        self.slots.get(&id).map(|slot| &slot.entity)
+    }
+
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut Entity> {
+        self.slots.get_mut(&id).map(|slot| &mut slot.entity)
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &Entity)> + '_ {
+        self.slots.iter().map(|(&id, slot)| (id, &slot.entity))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (EntityId, &mut Entity)> + '_ {
+        self.slots
+            .iter_mut()
+            .map(|(&id, slot)| (id, &mut slot.entity))
+    }
+
+    pub fn in_chunk(&self, chunk: (i32, i32)) -> impl Iterator<Item = (EntityId, &Entity)> + '_ {
+        self.chunks
+            .get(&chunk)
+            .into_iter()
+            .flatten()
+            .map(|&id| (id, &self.slots[&id].entity))
+    }
+
+    /// Every entity whose bounding box intersects `area`.
+    pub fn intersecting(
+        &self,
+        area: BoundingBox,
+    ) -> impl Iterator<Item = (EntityId, &Entity)> + '_ {
+        // Entities are filed by their position, which is at the bottom of their bounding box,
+        // so look one chunk further out in case a wide entity pokes over a chunk boundary.
+        let (min_x, min_z) = chunk_of(area.min);
+        let (max_x, max_z) = chunk_of(area.max);
+        (min_x - 1..=max_x + 1)
+            .flat_map(move |x| (min_z - 1..=max_z + 1).map(move |z| (x, z)))
+            .flat_map(|chunk| self.in_chunk(chunk))
+            .filter(move |(_, entity)| entity.body.bounding_box().intersects(&area))
+    }
+
+    /// Refiles entities that have moved into a different chunk.
+    pub fn update_chunks(&mut self) {
+        let mut moves = Vec::new();
+        for (&id, slot) in &mut self.slots {
+            let chunk = chunk_of(slot.entity.body.position);
+            if chunk != slot.chunk {
+                moves.push((id, slot.chunk, chunk));
+                slot.chunk = chunk;
+            }
+        }
+
+        for (id, from, to) in moves {
+            self.unfile(id, from);
+            self.chunks.entry(to).or_default().insert(id);
+        }
+    }
+
+    /// Removes every entity for which `keep` returns false.
+    pub fn retain(&mut self, mut keep: impl FnMut(&Entity) -> bool) {
+        let removed: Vec<_> = self
+            .iter()
+            .filter(|(_, entity)| !keep(entity))
+            .map(|(id, _)| id)
+            .collect();
+        for id in removed {
+            self.remove(id);
+        }
+    }
+
+    /// Removes every entity in a chunk that `is_loaded` says is no longer loaded.
+    pub fn unload(&mut self, is_loaded: impl Fn((i32, i32)) -> bool) {
+        let unloaded: Vec<_> = self
+            .chunks
+            .iter()
+            .filter(|(&chunk, _)| !is_loaded(chunk))
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect();
+        for id in unloaded {
+            self.remove(id);
+        }
+    }
+
+    fn unfile(&mut self, id: EntityId, chunk: (i32, i32)) {
+        if let Some(ids) = self.chunks.get_mut(&chunk) {
+            ids.remove(&id);
+            if ids.is_empty() {
+                self.chunks.remove(&chunk);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{vec3, Vec3};
+
+    use super::{item::DroppedItem, Entities, Entity};
+    use crate::{
+        bounding_box::BoundingBox, inventory::ItemStack, item::Item, terrain::block::Block,
+    };
+
+    fn entity_at(position: Vec3) -> Entity {
+        DroppedItem::entity(ItemStack::new(Item::Block(Block::DIRT), 1), position)
+    }
+
+    #[test]
+    fn finds_entities_by_area() {
+        let mut entities = Entities::new();
+        let near = entities.spawn(entity_at(vec3(1.0, 0.0, 1.0)));
+        let far = entities.spawn(entity_at(vec3(100.0, 0.0, 1.0)));
+
+        let area = BoundingBox::new(Vec3::ZERO, Vec3::splat(2.0));
+        let found: Vec<_> = entities.intersecting(area).map(|(id, _)| id).collect();
+
+        assert_eq!(found, [near]);
+        assert!(entities.get(far).is_some());
+    }
+
+    #[test]
+    fn moving_entities_are_refiled() {
+        let mut entities = Entities::new();
+        let id = entities.spawn(entity_at(vec3(1.0, 0.0, 1.0)));
+
+        entities.get_mut(id).unwrap().body.position.x = 40.0;
+        entities.update_chunks();
+
+        assert_eq!(entities.in_chunk((0, 0)).count(), 0);
+        assert_eq!(entities.in_chunk((2, 0)).count(), 1);
+    }
+
+    #[test]
+    fn unloads_entities_with_their_chunks() {
+        let mut entities = Entities::new();
+        entities.spawn(entity_at(vec3(1.0, 0.0, 1.0)));
+        let kept = entities.spawn(entity_at(vec3(-1.0, 0.0, 1.0)));
+
+        entities.unload(|chunk| chunk == (-1, 0));
+
+        assert_eq!(entities.len(), 1);
+        assert!(entities.get(kept).is_some());
+    }
+}
@@ -0,0 +1,34 @@
+/// Which set of rules the game is played by.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    /// Blocks take time to break and are used up when placed, and the player can be hurt and
+    /// gets hungry.
+    #[default]
+    Survival,
+    /// Blocks break instantly and never run out, and the player can fly and can't be hurt.
+    Creative,
+}
+
+impl GameMode {
+    pub const ALL: [Self; 2] = [Self::Survival, Self::Creative];
+
+    /// The name used for the mode in save files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Survival => "survival",
+            Self::Creative => "creative",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    /// The mode after this one, for switching between them.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Survival => Self::Creative,
+            Self::Creative => Self::Survival,
+        }
+    }
+}
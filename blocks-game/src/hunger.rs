@@ -0,0 +1,152 @@
+pub const MAX_FOOD: u32 = 20;
+/// Below this much food the player is too hungry to sprint.
+const SPRINT_FOOD: u32 = 7;
+/// At or above this much food the player slowly regains health.
+const REGENERATION_FOOD: u32 = 18;
+/// How much exhaustion it takes to use up one point of saturation or food.
+const EXHAUSTION_PER_FOOD: f32 = 4.0;
+const STARTING_SATURATION: f32 = 5.0;
+
+/// Something the player can eat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Food {
+    /// Food points restored.
+    pub nutrition: u32,
+    /// Saturation restored, which is used up before food.
+    pub saturation: f32,
+}
+
+impl Food {
+    pub const APPLE: Self = Self {
+        nutrition: 4,
+        saturation: 2.4,
+    };
+    pub const BREAD: Self = Self {
+        nutrition: 5,
+        saturation: 6.0,
+    };
+    pub const RAW_MEAT: Self = Self {
+        nutrition: 3,
+        saturation: 1.8,
+    };
+    pub const COOKED_MEAT: Self = Self {
+        nutrition: 8,
+        saturation: 12.8,
+    };
+}
+
+/// How well fed the player is. Actions add exhaustion, which drains saturation and then food.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunger {
+    pub food: u32,
+    /// Hidden reserve that is used up before food. Never more than `food`.
+    pub saturation: f32,
+    pub exhaustion: f32,
+}
+
+impl Default for Hunger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hunger {
+    pub fn new() -> Self {
+        Self {
+            food: MAX_FOOD,
+            saturation: STARTING_SATURATION,
+            exhaustion: 0.0,
+        }
+    }
+
+    /// How full the player is, from 0 to 1.
+    pub fn food_fraction(&self) -> f32 {
+        self.food as f32 / MAX_FOOD as f32
+    }
+
+    pub fn can_sprint(&self) -> bool {
+        self.food >= SPRINT_FOOD
+    }
+
+    pub fn can_regenerate(&self) -> bool {
+        self.food >= REGENERATION_FOOD
+    }
+
+    pub fn exhaust(&mut self, amount: f32) {
+        self.exhaustion += amount;
+        while self.exhaustion >= EXHAUSTION_PER_FOOD {
+            self.exhaustion -= EXHAUSTION_PER_FOOD;
+            if self.saturation > 0.0 {
+                self.saturation = (self.saturation - 1.0).max(0.0);
+            } else {
+                self.food = self.food.saturating_sub(1);
+            }
+        }
+    }
+
+    pub fn eat(&mut self, food: Food) {
+        self.food = (self.food + food.nutrition).min(MAX_FOOD);
+        self.saturation = (self.saturation + food.saturation).min(self.food as f32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Food, Hunger, MAX_FOOD};
+
+    #[test]
+    fn exhaustion_uses_saturation_before_food() {
+        let mut hunger = Hunger::new();
+        hunger.saturation = 1.0;
+
+        hunger.exhaust(4.0);
+        assert_eq!(hunger.saturation, 0.0);
+        assert_eq!(hunger.food, MAX_FOOD);
+
+        hunger.exhaust(4.0);
+        assert_eq!(hunger.food, MAX_FOOD - 1);
+    }
+
+    #[test]
+    fn exhaustion_carries_over() {
+        let mut hunger = Hunger::new();
+        hunger.saturation = 0.0;
+
+        for _ in 0..10 {
+            hunger.exhaust(1.0);
+        }
+
+        assert_eq!(hunger.food, MAX_FOOD - 2);
+        assert_eq!(hunger.exhaustion, 2.0);
+    }
+
+    #[test]
+    fn low_food_disables_sprinting_and_regeneration() {
+        let mut hunger = Hunger::new();
+        assert!(hunger.can_sprint());
+        assert!(hunger.can_regenerate());
+
+        hunger.food = 10;
+        assert!(hunger.can_sprint());
+        assert!(!hunger.can_regenerate());
+
+        hunger.food = 6;
+        assert!(!hunger.can_sprint());
+    }
+
+    #[test]
+    fn eating_restores_food_up_to_the_limit() {
+        let mut hunger = Hunger::new();
+        hunger.food = 2;
+        hunger.saturation = 0.0;
+
+        hunger.eat(Food::APPLE);
+        assert_eq!(hunger.food, 6);
+        assert_eq!(hunger.saturation, 2.4);
+
+        hunger.eat(Food::COOKED_MEAT);
+        hunger.eat(Food::COOKED_MEAT);
+        assert_eq!(hunger.food, MAX_FOOD);
+        assert!(hunger.saturation <= hunger.food as f32);
+    }
+}
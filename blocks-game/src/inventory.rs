@@ -0,0 +1,199 @@
+use crate::{
+    item::{Item, ToolKind, ToolTier},
+    terrain::block::Block,
+};
+
+pub const HOTBAR_SIZE: usize = 9;
+pub const INVENTORY_SIZE: usize = 36;
+
+/// Everything available in creative mode, starting with what fits in the hotbar.
+const CREATIVE_PALETTE: [Item; 10] = [
+    Item::Block(Block::STONE),
+    Item::Block(Block::GRASS),
+    Item::Block(Block::DIRT),
+    Item::Block(Block::VINES),
+    Item::Block(Block::LADDER),
+    Item::Tool(ToolKind::Pickaxe, ToolTier::Diamond),
+    Item::Tool(ToolKind::Shovel, ToolTier::Diamond),
+    Item::Apple,
+    Item::Bread,
+    Item::CookedMeat,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemStack {
+    pub item: Item,
+    pub count: u32,
+}
+
+impl ItemStack {
+    pub fn new(item: Item, count: u32) -> Self {
+        Self { item, count }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inventory {
+    /// The first `HOTBAR_SIZE` slots make up the hotbar.
+    pub slots: [Option<ItemStack>; INVENTORY_SIZE],
+    selected: usize,
+    /// Whether items are never used up, as in creative mode.
+    pub infinite: bool,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self {
+            slots: [None; INVENTORY_SIZE],
+            selected: 0,
+            infinite: false,
+        }
+    }
+
+    /// An infinite inventory with one of every item in the creative palette.
+    pub fn creative() -> Self {
+        let mut inventory = Self::new();
+        for (slot, item) in inventory.slots.iter_mut().zip(CREATIVE_PALETTE) {
+            *slot = Some(ItemStack::new(item, 1));
+        }
+        inventory.infinite = true;
+        inventory
+    }
+
+    pub fn hotbar(&self) -> &[Option<ItemStack>] {
+        &self.slots[..HOTBAR_SIZE]
+    }
+
+    /// The selected hotbar slot.
+    pub fn selected_slot(&self) -> usize {
+        self.selected
+    }
+
+    /// Selects a hotbar slot. Does nothing if `slot` is outside the hotbar.
+    pub fn select(&mut self, slot: usize) {
+        if slot < HOTBAR_SIZE {
+            self.selected = slot;
+        }
+    }
+
+    /// Moves the selection along the hotbar by `steps`, wrapping around at either end.
+    pub fn scroll(&mut self, steps: i32) {
+        self.selected = (self.selected as i32 + steps).rem_euclid(HOTBAR_SIZE as i32) as usize;
+    }
+
+    pub fn selected_stack(&self) -> Option<ItemStack> {
+        self.slots[self.selected]
+    }
+
+    /// Adds `count` of `item`, topping up existing stacks before filling empty slots. Returns
+    /// how many didn't fit.
+    pub fn add(&mut self, item: Item, count: u32) -> u32 {
+        if self.infinite {
+            return 0;
+        }
+
+        let mut remaining = count;
+        for stack in self.slots.iter_mut().flatten() {
+            if stack.item == item {
+                let added = remaining.min(item.max_stack_size().saturating_sub(stack.count));
+                stack.count += added;
+                remaining -= added;
+            }
+        }
+        for slot in self.slots.iter_mut().filter(|s| s.is_none()) {
+            if remaining == 0 {
+                break;
+            }
+            let added = remaining.min(item.max_stack_size());
+            *slot = Some(ItemStack::new(item, added));
+            remaining -= added;
+        }
+        remaining
+    }
+
+    /// Takes one of the selected item, unless the inventory is infinite.
+    pub fn take_selected(&mut self) -> Option<Item> {
+        let slot = &mut self.slots[self.selected];
+        let stack = slot.as_mut()?;
+        let item = stack.item;
+        if !self.infinite {
+            stack.count -= 1;
+            if stack.count == 0 {
+                *slot = None;
+            }
+        }
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Inventory, ItemStack, HOTBAR_SIZE, INVENTORY_SIZE};
+    use crate::{item::Item, terrain::block::Block};
+
+    const DIRT: Item = Item::Block(Block::DIRT);
+    const STONE: Item = Item::Block(Block::STONE);
+
+    #[test]
+    fn adding_tops_up_existing_stacks_first() {
+        let mut inventory = Inventory::new();
+        inventory.slots[3] = Some(ItemStack::new(DIRT, 60));
+
+        assert_eq!(inventory.add(DIRT, 10), 0);
+
+        assert_eq!(inventory.slots[3], Some(ItemStack::new(DIRT, 64)));
+        assert_eq!(inventory.slots[0], Some(ItemStack::new(DIRT, 6)));
+    }
+
+    #[test]
+    fn adding_returns_what_does_not_fit() {
+        let mut inventory = Inventory::new();
+        inventory.slots = [Some(ItemStack::new(STONE, 64)); INVENTORY_SIZE];
+        inventory.slots[5] = Some(ItemStack::new(DIRT, 63));
+
+        assert_eq!(inventory.add(DIRT, 3), 2);
+        assert_eq!(inventory.add(Item::Apple, 1), 1);
+    }
+
+    #[test]
+    fn taking_empties_the_slot() {
+        let mut inventory = Inventory::new();
+        inventory.add(STONE, 2);
+
+        assert_eq!(inventory.take_selected(), Some(STONE));
+        assert_eq!(inventory.take_selected(), Some(STONE));
+        assert_eq!(inventory.take_selected(), None);
+        assert_eq!(inventory.selected_stack(), None);
+    }
+
+    #[test]
+    fn creative_inventory_is_never_used_up() {
+        let mut inventory = Inventory::creative();
+
+        for _ in 0..100 {
+            assert_eq!(inventory.take_selected(), Some(STONE));
+        }
+        assert_eq!(inventory.add(DIRT, 10), 0);
+        assert_eq!(inventory.selected_stack(), Some(ItemStack::new(STONE, 1)));
+    }
+
+    #[test]
+    fn scrolling_wraps_around_the_hotbar() {
+        let mut inventory = Inventory::new();
+
+        inventory.scroll(-1);
+        assert_eq!(inventory.selected_slot(), HOTBAR_SIZE - 1);
+
+        inventory.scroll(2);
+        assert_eq!(inventory.selected_slot(), 1);
+
+        inventory.select(HOTBAR_SIZE);
+        assert_eq!(inventory.selected_slot(), 1);
+    }
+}
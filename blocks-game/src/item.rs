@@ -0,0 +1,239 @@
+use crate::{hunger::Food, terrain::block::Block};
+
+const MAX_STACK_SIZE: u32 = 64;
+/// How much slower blocks are to break when they need a tool that isn't being used.
+const WRONG_TOOL_PENALTY: f32 = 10.0 / 3.0;
+/// Seconds to break a block, per unit of hardness, with the right tool and no speed bonus.
+const BREAK_TIME_PER_HARDNESS: f32 = 1.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolKind {
+    Pickaxe,
+    Shovel,
+}
+
+impl ToolKind {
+    pub const ALL: [Self; 2] = [Self::Pickaxe, Self::Shovel];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pickaxe => "pickaxe",
+            Self::Shovel => "shovel",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ToolTier {
+    Wood,
+    Stone,
+    Iron,
+    Diamond,
+}
+
+impl ToolTier {
+    pub const ALL: [Self; 4] = [Self::Wood, Self::Stone, Self::Iron, Self::Diamond];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Wood => "wooden",
+            Self::Stone => "stone",
+            Self::Iron => "iron",
+            Self::Diamond => "diamond",
+        }
+    }
+
+    /// How many times faster than a bare hand this tier breaks blocks it is suited to.
+    pub fn speed(self) -> f32 {
+        match self {
+            Self::Wood => 2.0,
+            Self::Stone => 4.0,
+            Self::Iron => 6.0,
+            Self::Diamond => 8.0,
+        }
+    }
+}
+
+/// Something that can be held in an inventory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Item {
+    Block(Block),
+    Tool(ToolKind, ToolTier),
+    Apple,
+    Bread,
+    RawMeat,
+    CookedMeat,
+    Bone,
+}
+
+impl Item {
+    /// Every item there is.
+    pub fn all() -> impl Iterator<Item = Self> {
+        let blocks = Block::ALL
+            .into_iter()
+            .filter(|&block| block != Block::AIR)
+            .map(Self::Block);
+        let tools = ToolKind::ALL
+            .into_iter()
+            .flat_map(|kind| ToolTier::ALL.map(|tier| Self::Tool(kind, tier)));
+        let others = [
+            Self::Apple,
+            Self::Bread,
+            Self::RawMeat,
+            Self::CookedMeat,
+            Self::Bone,
+        ];
+        blocks.chain(tools).chain(others)
+    }
+
+    /// The name used for the item in data files, such as `stone_pickaxe` or `apple`.
+    pub fn name(self) -> String {
+        match self {
+            Self::Block(block) => block.name().to_string(),
+            Self::Tool(kind, tier) => format!("{}_{}", tier.name(), kind.name()),
+            Self::Apple => "apple".to_string(),
+            Self::Bread => "bread".to_string(),
+            Self::RawMeat => "raw_meat".to_string(),
+            Self::CookedMeat => "cooked_meat".to_string(),
+            Self::Bone => "bone".to_string(),
+        }
+    }
+
+    /// The item called `name` in data files.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().find(|item| item.name() == name)
+    }
+
+    /// The item left behind when `block` is broken, if any.
+    pub fn dropped_by(block: Block) -> Option<Self> {
+        match block {
+            Block::AIR | Block::WATER | Block::SNOW => None,
+            Block::GRASS => Some(Self::Block(Block::DIRT)),
+            block => Some(Self::Block(block.unoriented())),
+        }
+    }
+
+    pub fn max_stack_size(self) -> u32 {
+        match self {
+            Self::Tool(..) => 1,
+            _ => MAX_STACK_SIZE,
+        }
+    }
+
+    /// The block this item places, if it is a block.
+    pub fn block(self) -> Option<Block> {
+        match self {
+            Self::Block(block) => Some(block),
+            _ => None,
+        }
+    }
+
+    pub fn food(self) -> Option<Food> {
+        match self {
+            Self::Apple => Some(Food::APPLE),
+            Self::Bread => Some(Food::BREAD),
+            Self::RawMeat => Some(Food::RAW_MEAT),
+            Self::CookedMeat => Some(Food::COOKED_MEAT),
+            Self::Block(_) | Self::Tool(..) | Self::Bone => None,
+        }
+    }
+
+    /// How many times faster than a bare hand this item breaks `block`.
+    pub fn mining_speed(self, block: Block) -> f32 {
+        match self {
+            Self::Tool(kind, tier) if Some(kind) == preferred_tool(block) => tier.speed(),
+            _ => 1.0,
+        }
+    }
+}
+
+/// Seconds it takes to break `block` while holding `held`, or `None` if it can't be broken.
+pub fn break_time(block: Block, held: Option<Item>) -> Option<f32> {
+    let hardness = block.hardness()?;
+    let speed = held.map_or(1.0, |item| item.mining_speed(block));
+    let penalty = if can_harvest(block, held) {
+        1.0
+    } else {
+        WRONG_TOOL_PENALTY
+    };
+    Some(hardness * BREAK_TIME_PER_HARDNESS * penalty / speed)
+}
+
+/// Whether breaking `block` while holding `held` gives its drop.
+pub fn can_harvest(block: Block, held: Option<Item>) -> bool {
+    let right_tool = matches!(
+        held,
+        Some(Item::Tool(kind, _)) if Some(kind) == preferred_tool(block)
+    );
+    right_tool || !needs_tool(block)
+}
+
+/// The kind of tool that breaks `block` fastest.
+fn preferred_tool(block: Block) -> Option<ToolKind> {
+    match block {
+        Block::STONE => Some(ToolKind::Pickaxe),
+        Block::GRASS | Block::DIRT => Some(ToolKind::Shovel),
+        _ => None,
+    }
+}
+
+/// Whether `block` only drops anything when broken with its preferred tool.
+fn needs_tool(block: Block) -> bool {
+    block == Block::STONE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{break_time, can_harvest, Item, ToolKind, ToolTier};
+    use crate::terrain::block::Block;
+
+    const WOODEN_PICKAXE: Item = Item::Tool(ToolKind::Pickaxe, ToolTier::Wood);
+    const DIAMOND_PICKAXE: Item = Item::Tool(ToolKind::Pickaxe, ToolTier::Diamond);
+    const STONE_SHOVEL: Item = Item::Tool(ToolKind::Shovel, ToolTier::Stone);
+    const STONE_PICKAXE: Item = Item::Tool(ToolKind::Pickaxe, ToolTier::Stone);
+
+    #[test]
+    fn better_tools_break_faster() {
+        let by_hand = break_time(Block::STONE, None).unwrap();
+        let wood = break_time(Block::STONE, Some(WOODEN_PICKAXE)).unwrap();
+        let diamond = break_time(Block::STONE, Some(DIAMOND_PICKAXE)).unwrap();
+
+        assert!(by_hand > wood);
+        assert!(wood > diamond);
+    }
+
+    #[test]
+    fn only_the_right_tool_helps() {
+        assert_eq!(
+            break_time(Block::DIRT, Some(WOODEN_PICKAXE)),
+            break_time(Block::DIRT, None)
+        );
+        assert!(
+            break_time(Block::DIRT, Some(STONE_SHOVEL)).unwrap()
+                < break_time(Block::DIRT, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn stone_needs_a_pickaxe_to_drop() {
+        assert!(!can_harvest(Block::STONE, None));
+        assert!(!can_harvest(Block::STONE, Some(STONE_SHOVEL)));
+        assert!(can_harvest(Block::STONE, Some(WOODEN_PICKAXE)));
+        assert!(can_harvest(Block::DIRT, None));
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for item in Item::all() {
+            assert_eq!(Item::from_name(&item.name()), Some(item));
+        }
+        assert_eq!(Item::from_name("stone_pickaxe"), Some(STONE_PICKAXE));
+        assert_eq!(Item::from_name("air"), None);
+    }
+
+    #[test]
+    fn some_blocks_cannot_be_broken() {
+        assert_eq!(break_time(Block::AIR, None), None);
+        assert_eq!(break_time(Block::WATER, Some(DIAMOND_PICKAXE)), None);
+    }
+}
@@ -1,31 +1,1024 @@
-use crate::{player::Player, terrain::Terrain};
+use std::{mem, ops::RangeInclusive};
+
+use glam::{ivec3, vec3, IVec3, Vec3};
+
+use crate::{
+    crafting::{Click, CraftingScreen, Recipes, Slot},
+    entity::{
+        item::{self as dropped_item, DroppedItem, PICKUP_RANGE},
+        mob::{self, DESPAWN_DISTANCE},
+        Entities, EntityId, EntityKind,
+    },
+    game_mode::GameMode,
+    inventory::{Inventory, ItemStack},
+    item::Item,
+    mining::Mining,
+    player::{MovementMode, Player},
+    save::SaveData,
+    terrain::{
+        block::Block,
+        generation::{
+            climate::{self, Precipitation},
+            random::Random,
+        },
+        Terrain,
+    },
+    time::WorldTime,
+    weather::{Lightning, Weather},
+};
 
 pub mod bounding_box;
+pub mod crafting;
+pub mod entity;
+pub mod game_mode;
+pub mod hunger;
+pub mod inventory;
+pub mod item;
+pub mod mining;
+pub mod parse_error;
+pub mod pathfinding;
+pub mod physics;
 pub mod player;
+pub mod save;
+pub mod spawn;
 pub mod terrain;
+pub mod time;
 pub mod util;
+pub mod weather;
 
-const MAX_DELTA_TIME: f32 = 0.03;
+const DEFAULT_TICK_RATE: f32 = 60.0;
+/// The range of ticks per second the game can run at. Outside of it ticks are either too far
+/// apart to play or too short to keep track of.
+pub const TICK_RATES: RangeInclusive<f32> = 1.0..=1000.0;
+/// The most ticks a single frame can run, so that the game slows down instead of falling
+/// further and further behind when ticks take longer than they simulate.
+const MAX_TICKS_PER_FRAME: u32 = 100;
+/// The most time a single frame can advance the game by, so that a long stall doesn't cause a
+/// burst of ticks to catch up.
+const MAX_FRAME_TIME: f32 = 0.25;
+/// Seconds between attempts to spawn a mob.
+const SPAWN_INTERVAL: f32 = 1.0;
+/// Damage the player deals to mobs with each hit.
+const ATTACK_DAMAGE: f32 = 2.0;
+/// How far from the player lightning strikes and snow settles.
+const WEATHER_RADIUS: f32 = 32.0;
+/// Damage dealt by lightning to anything within `LIGHTNING_RADIUS` of where it strikes.
+const LIGHTNING_DAMAGE: f32 = 5.0;
+const LIGHTNING_RADIUS: f32 = 3.0;
+/// Average seconds between snow settling near the player in the heaviest weather.
+const SNOW_INTERVAL: f32 = 1.0 / 60.0;
 
 pub struct Game {
     pub player: Player,
     pub terrain: Terrain,
+    pub mining: Mining,
+    pub recipes: Recipes,
+    /// The crafting screen, while it is open.
+    pub crafting: Option<CraftingScreen>,
+    pub time: WorldTime,
+    pub weather: Weather,
+    game_mode: GameMode,
+    /// The player's own inventory, put aside while playing creative.
+    survival_inventory: Option<Inventory>,
+    /// Where new players appear, or `None` until the terrain there has been generated and a spot
+    /// found. Nothing moves until then.
+    pub world_spawn: Option<Vec3>,
+    /// Everything in the world other than the player, such as dropped items and mobs.
+    pub entities: Entities,
+    random: Random,
+    /// Seconds until the next attempt to spawn a mob.
+    spawn_timer: f32,
+    tick_duration: f32,
+    /// Time that has passed but not yet been simulated, always less than one tick.
+    accumulator: f32,
+    previous_head_position: Vec3,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Game {
     pub fn new() -> Self {
-        Self {
-            player: Player::new(),
-            terrain: Terrain::new(),
+        Self::with_tick_rate(DEFAULT_TICK_RATE)
+    }
+
+    pub fn with_tick_rate(ticks_per_second: f32) -> Self {
+        assert!(
+            TICK_RATES.contains(&ticks_per_second),
+            "tick rate must be within {TICK_RATES:?}"
+        );
+        let player = Player::new();
+        let terrain = Terrain::new();
+        let mut game = Self {
+            previous_head_position: player.head_position(),
+            player,
+            random: Random::with_seed(terrain.seed),
+            terrain,
+            mining: Mining::default(),
+            recipes: Recipes::builtin(),
+            crafting: None,
+            time: WorldTime::new(),
+            weather: Weather::new(),
+            game_mode: GameMode::default(),
+            survival_inventory: None,
+            world_spawn: None,
+            entities: Entities::new(),
+            spawn_timer: SPAWN_INTERVAL,
+            tick_duration: 1.0 / ticks_per_second,
+            accumulator: 0.0,
+        };
+        game.set_game_mode(GameMode::default());
+        game
+    }
+
+    pub fn game_mode(&self) -> GameMode {
+        self.game_mode
+    }
+
+    /// Switches to playing by the rules of `mode`.
+    pub fn set_game_mode(&mut self, mode: GameMode) {
+        self.game_mode = mode;
+        let creative = mode == GameMode::Creative;
+        self.player.can_fly = creative;
+        self.player.invulnerable = creative;
+        if creative {
+            if self.survival_inventory.is_none() {
+                let inventory = mem::replace(&mut self.player.inventory, Inventory::creative());
+                self.survival_inventory = Some(inventory);
+            }
+        } else if let Some(inventory) = self.survival_inventory.take() {
+            self.player.inventory = inventory;
+        }
+        // Noclip and spectating are kinds of flying too, so they go as well.
+        if !creative && self.player.movement_mode.is_flying() {
+            self.player.movement_mode = MovementMode::Walking;
+        }
+    }
+
+    /// Switches the player to `mode`, or back to walking if they're already in it. Only players
+    /// who are allowed to fly can leave the ground.
+    pub fn toggle_movement_mode(&mut self, mode: MovementMode) {
+        if self.player.can_fly || self.game_mode == GameMode::Creative {
+            self.player.toggle_movement_mode(mode);
+        }
+    }
+
+    /// The world's settings, to keep for next time.
+    pub fn save(&self) -> SaveData {
+        SaveData {
+            seed: self.terrain.seed,
+            game_mode: self.game_mode,
+        }
+    }
+
+    /// Restores the settings kept from a previous session. A different seed starts the terrain
+    /// over, so this is meant to be called before play starts.
+    pub fn load(&mut self, data: &SaveData) {
+        if data.seed != self.terrain.seed {
+            self.terrain = Terrain::with_seed(data.seed);
+            self.random = Random::with_seed(data.seed);
+            self.world_spawn = None;
         }
+        self.set_game_mode(data.game_mode);
     }
 
+    /// Ticks per second.
+    pub fn tick_rate(&self) -> f32 {
+        1.0 / self.tick_duration
+    }
+
+    pub fn set_tick_rate(&mut self, ticks_per_second: f32) {
+        assert!(
+            TICK_RATES.contains(&ticks_per_second),
+            "tick rate must be within {TICK_RATES:?}"
+        );
+        self.tick_duration = 1.0 / ticks_per_second;
+    }
+
+    /// Advances the game by `delta_time` seconds of real time, running as many fixed ticks as
+    /// fit in it, up to `MAX_TICKS_PER_FRAME`.
     pub fn update(&mut self, delta_time: f32) {
-        let delta_time = delta_time.min(MAX_DELTA_TIME);
+        self.accumulator += delta_time.min(MAX_FRAME_TIME);
+        for _ in 0..MAX_TICKS_PER_FRAME {
+            if self.accumulator < self.tick_duration {
+                return;
+            }
+            self.accumulator -= self.tick_duration;
+            self.tick();
+        }
+        // Drop the time there wasn't room to simulate.
+        self.accumulator %= self.tick_duration;
+    }
 
+    fn tick(&mut self) {
+        self.previous_head_position = self.player.head_position();
+        if self.world_spawn.is_none() {
+            self.find_world_spawn();
+            return;
+        }
+
+        self.time.advance(self.tick_duration);
         self.terrain.generate(self.player.position.as_ivec3());
+        self.update_weather();
+
+        self.player.update(self.tick_duration, &self.terrain);
+
+        if !self.player.can_interact() {
+            self.mining.set_target(None);
+        }
+        let held = self.held_item();
+        let broken = match self.game_mode {
+            GameMode::Survival => self.mining.update(self.tick_duration, &self.terrain, held),
+            GameMode::Creative => self
+                .mining
+                .update_instant(self.tick_duration, &self.terrain),
+        };
+        if let Some(position) = broken {
+            self.break_block(position);
+        }
+
+        self.update_entities();
+    }
+
+    /// Generates terrain around the origin and looks for somewhere to spawn there, moving the
+    /// player to it once found.
+    fn find_world_spawn(&mut self) {
+        self.terrain.generate(IVec3::ZERO);
+        let bounds = self.player.bounding_box() - self.player.position;
+        let Some(spawn) = spawn::find_spawn_point(&self.terrain, bounds) else {
+            return;
+        };
+        self.world_spawn = Some(spawn);
+        self.player.spawn_point = spawn;
+        self.player.position = spawn;
+        self.player.velocity = Vec3::ZERO;
+        self.previous_head_position = self.player.head_position();
+    }
+
+    fn update_entities(&mut self) {
+        for (_, entity) in self.entities.iter_mut() {
+            entity.previous_position = entity.body.position;
+            if let EntityKind::Mob(mob) = &mut entity.kind {
+                let damage = mob.update(
+                    &mut entity.body,
+                    self.tick_duration,
+                    &self.terrain,
+                    &self.player,
+                    &mut self.random,
+                );
+                self.player.damage(damage);
+            }
+            entity.update(self.tick_duration, &self.terrain);
+        }
+        self.entities.update_chunks();
+        dropped_item::merge_nearby(&mut self.entities);
+
+        if self.player.can_interact() {
+            let reach = self.player.bounding_box().expand_by(PICKUP_RANGE);
+            let picked_up: Vec<_> = self
+                .entities
+                .intersecting(reach)
+                .filter(|(_, entity)| dropped_item::can_be_picked_up(entity.age))
+                .map(|(id, _)| id)
+                .collect();
+            for id in picked_up {
+                let entity = self.entities.get_mut(id).unwrap();
+                if let Some(item) = entity.as_item_mut() {
+                    item.stack.count = self.player.inventory.add(item.stack.item, item.stack.count);
+                }
+            }
+        }
+
+        self.drop_mob_loot();
+        let player_position = self.player.position;
+        self.entities.retain(|entity| {
+            let too_far = entity.as_mob().is_some()
+                && entity.body.position.distance(player_position) > DESPAWN_DISTANCE;
+            !entity.is_expired() && !too_far
+        });
+        let chunks = &self.terrain.chunks;
+        self.entities.unload(|chunk| chunks.contains_key(&chunk));
+
+        self.spawn_timer -= self.tick_duration;
+        if self.spawn_timer <= 0.0 {
+            self.spawn_timer += SPAWN_INTERVAL;
+            let sky_light = self.sky_light();
+            mob::spawn_random(
+                &mut self.entities,
+                &self.terrain,
+                self.player.position,
+                sky_light,
+                &mut self.random,
+            );
+        }
+    }
+
+    fn update_weather(&mut self) {
+        if self.weather.update(self.tick_duration, &mut self.random) {
+            if let Some(column) = self.random_weather_column() {
+                self.strike_lightning(column.as_vec3() + vec3(0.5, 0.0, 0.5));
+            }
+        }
+        self.accumulate_snow();
+    }
+
+    /// A random block just above the surface near the player, if its column is loaded.
+    fn random_weather_column(&mut self) -> Option<IVec3> {
+        let offset = vec3(
+            self.random.next_float() * 2.0 - 1.0,
+            0.0,
+            self.random.next_float() * 2.0 - 1.0,
+        );
+        let column = (self.player.position + WEATHER_RADIUS * offset)
+            .floor()
+            .as_ivec3();
+        let surface = self.terrain.surface_height(column.x, column.z)?;
+        Some(ivec3(column.x, surface + 1, column.z))
+    }
+
+    /// Now and then leaves a layer of snow on the ground where it's cold enough to snow, more
+    /// often the heavier the weather.
+    fn accumulate_snow(&mut self) {
+        let chance = self.weather.intensity() * self.tick_duration / SNOW_INTERVAL;
+        if self.random.next_float() >= chance {
+            return;
+        }
+        let Some(position) = self.random_weather_column() else {
+            return;
+        };
+        if self.terrain.block(position) == Block::AIR
+            && climate::precipitation(self.terrain.seed, position) == Precipitation::Snow
+        {
+            self.terrain.set_block(position, Block::SNOW);
+        }
+    }
+
+    /// Strikes lightning at `position`, hurting the player and any mobs close by.
+    pub fn strike_lightning(&mut self, position: Vec3) {
+        self.weather.lightning = Some(Lightning { position, age: 0.0 });
+
+        if self.player.position.distance(position) <= LIGHTNING_RADIUS {
+            self.player.damage(LIGHTNING_DAMAGE);
+        }
+        for (_, entity) in self.entities.iter_mut() {
+            if entity.body.position.distance(position) > LIGHTNING_RADIUS {
+                continue;
+            }
+            if let EntityKind::Mob(mob) = &mut entity.kind {
+                mob.hurt(&mut entity.body, LIGHTNING_DAMAGE, position);
+            }
+        }
+    }
+
+    /// The colour of the sky, taking both the time of day and the weather into account.
+    pub fn sky_color(&self) -> Vec3 {
+        self.weather.tint_sky(self.time.sky_color())
+    }
+
+    /// How brightly lit things in the open are, as a multiplier on their colour.
+    pub fn brightness(&self) -> f32 {
+        match self.weather.lightning {
+            Some(_) => 1.0,
+            None => self.time.brightness() * (1.0 - self.weather.darkening()),
+        }
+    }
+
+    /// The light level under open sky, which is lower at night and in bad weather.
+    pub fn sky_light(&self) -> u8 {
+        (self.time.sky_light() as f32 * (1.0 - self.weather.darkening())).round() as u8
+    }
+
+    /// Leaves behind the drops of every mob that has just died.
+    fn drop_mob_loot(&mut self) {
+        let loot: Vec<_> = self
+            .entities
+            .iter()
+            .filter_map(|(_, entity)| Some((entity.as_mob()?, entity.body.position)))
+            .filter(|(mob, _)| mob.is_dead())
+            .map(|(mob, position)| (mob.drops(&mut self.random), position))
+            .collect();
+        for (stack, position) in loot {
+            self.entities.spawn(DroppedItem::entity(stack, position));
+        }
+    }
+
+    /// Hits the mob `id`, knocking it back. Returns whether there was a mob to hit.
+    pub fn attack(&mut self, id: EntityId) -> bool {
+        if !self.player.can_interact() {
+            return false;
+        }
+        let Some(entity) = self.entities.get_mut(id) else {
+            return false;
+        };
+        let EntityKind::Mob(mob) = &mut entity.kind else {
+            return false;
+        };
+        mob.hurt(&mut entity.body, ATTACK_DAMAGE, self.player.position);
+        true
+    }
+
+    fn held_item(&self) -> Option<Item> {
+        self.player
+            .inventory
+            .selected_stack()
+            .map(|stack| stack.item)
+    }
+
+    /// Removes the block at `position`, dropping whatever it leaves behind if the player is
+    /// holding the right tool and playing survival.
+    pub fn break_block(&mut self, position: IVec3) {
+        let block = self.terrain.block(position);
+        self.terrain.set_block(position, Block::AIR);
+        // Blocks never run out in creative, so there's no need to collect them.
+        if self.game_mode == GameMode::Creative || !item::can_harvest(block, self.held_item()) {
+            return;
+        }
+        if let Some(item) = Item::dropped_by(block) {
+            let stack = ItemStack::new(item, 1);
+            self.entities
+                .spawn(DroppedItem::from_block(stack, position.as_vec3()));
+        }
+    }
+
+    /// Places the player's selected block at `position`, unless it would overlap the player.
+    /// `face` is the side of the block it was placed against, which orients blocks such as
+    /// ladders. Returns whether a block was placed.
+    pub fn place_block(&mut self, position: IVec3, face: IVec3) -> bool {
+        let Some(block) = self
+            .player
+            .inventory
+            .selected_stack()
+            .and_then(|stack| stack.item.block())
+            .and_then(|block| block.placed_against(face))
+        else {
+            return false;
+        };
+        if let Some(collision) = block.collision_box(position) {
+            let blocked = collision.intersects(&self.player.bounding_box())
+                || self
+                    .entities
+                    .intersecting(collision)
+                    .any(|(_, entity)| entity.as_mob().is_some());
+            if blocked {
+                return false;
+            }
+            // Dropped items are small enough to sit on top of the new block instead.
+            let buried: Vec<_> = self
+                .entities
+                .intersecting(collision)
+                .map(|(id, _)| id)
+                .collect();
+            for id in buried {
+                let body = &mut self.entities.get_mut(id).unwrap().body;
+                body.position.y = collision.max.y;
+                body.velocity = Vec3::ZERO;
+            }
+        }
+
+        self.player.inventory.take_selected();
+        self.terrain.set_block(position, block);
+        true
+    }
+
+    pub fn open_crafting(&mut self) {
+        self.crafting.get_or_insert_with(CraftingScreen::new);
+    }
+
+    /// Closes the crafting screen, putting everything left on it back into the inventory and
+    /// dropping whatever doesn't fit. An infinite inventory doesn't keep anything given to it,
+    /// so everything is dropped.
+    pub fn close_crafting(&mut self) {
+        let Some(mut screen) = self.crafting.take() else {
+            return;
+        };
+        for stack in screen.take_all() {
+            let left_over = if self.player.inventory.infinite {
+                stack.count
+            } else {
+                self.player.inventory.add(stack.item, stack.count)
+            };
+            if left_over > 0 {
+                let stack = ItemStack::new(stack.item, left_over);
+                self.entities
+                    .spawn(DroppedItem::entity(stack, self.player.position));
+            }
+        }
+    }
+
+    /// Clicks a slot on the crafting screen, if it's open.
+    pub fn click_crafting_slot(&mut self, slot: Slot, click: Click) {
+        if let Some(screen) = &mut self.crafting {
+            screen.click(slot, click, &mut self.player.inventory, &self.recipes);
+        }
+    }
+
+    /// How far the current frame is between the previous tick and the next, from 0 to 1.
+    pub fn interpolation(&self) -> f32 {
+        self.accumulator / self.tick_duration
+    }
+
+    /// The player's head position, smoothed between the previous and current ticks.
+    pub fn interpolated_head_position(&self) -> Vec3 {
+        self.previous_head_position
+            .lerp(self.player.head_position(), self.interpolation())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{ivec3, vec3, IVec3};
+
+    use super::{Game, GameMode, MovementMode, SaveData, MAX_TICKS_PER_FRAME};
+    use crate::{
+        crafting::{Click, Slot},
+        entity::{
+            item::DroppedItem,
+            mob::{Mob, MobKind, DESPAWN_DISTANCE},
+        },
+        inventory::ItemStack,
+        item::Item,
+        terrain::{
+            block::{Block, Facing, FLAT_THICKNESS},
+            generation::{random::Random, SEA_LEVEL},
+            Terrain,
+        },
+        time::DAY_LENGTH,
+        weather::WeatherKind,
+    };
+
+    fn game_with_empty_chunk() -> Game {
+        let mut game = Game::new();
+        game.terrain = Terrain::empty(1..=1, 1..=1);
+        game.world_spawn = Some(game.player.position);
+        game
+    }
+
+    #[test]
+    fn simulation_does_not_depend_on_frame_rate() {
+        // Powers of two so that the frame times add up exactly.
+        let mut slow = Game::with_tick_rate(16.0);
+        let mut fast = Game::with_tick_rate(16.0);
+
+        for _ in 0..4 {
+            slow.update(0.25);
+        }
+        for _ in 0..32 {
+            fast.update(1.0 / 32.0);
+        }
+
+        assert_eq!(slow.player.position, fast.player.position);
+    }
+
+    #[test]
+    fn limits_ticks_per_frame() {
+        let mut game = Game::with_tick_rate(1000.0);
+        game.world_spawn = Some(game.player.position);
+        let start = game.time.time_of_day();
+
+        game.update(0.25);
+
+        let simulated = (game.time.time_of_day() - start) * DAY_LENGTH;
+        assert!((simulated - MAX_TICKS_PER_FRAME as f32 / 1000.0).abs() < 1e-3);
+        assert!(game.accumulator < game.tick_duration);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_tick_rates_out_of_range() {
+        Game::with_tick_rate(f32::INFINITY);
+    }
+
+    #[test]
+    fn keeps_leftover_time_for_interpolation() {
+        let mut game = Game::with_tick_rate(10.0);
+        while game.world_spawn.is_none() {
+            game.tick();
+        }
+        // Up in the air, so that the player falls.
+        game.player.position.y += 10.0;
+
+        game.update(0.25);
+
+        assert!((game.interpolation() - 0.5).abs() < 1e-4);
+        let previous = game.previous_head_position;
+        let current = game.player.head_position();
+        assert!(current != previous);
+        assert!((game.interpolated_head_position() - previous.lerp(current, 0.5)).length() < 1e-4);
+    }
+
+    #[test]
+    fn interpolates_entities_between_ticks() {
+        let mut game = Game::with_tick_rate(10.0);
+        while game.world_spawn.is_none() {
+            game.tick();
+        }
+        let above = game.player.position + vec3(2.0, 10.0, 0.0);
+        let id = game
+            .entities
+            .spawn(DroppedItem::entity(ItemStack::new(Item::Apple, 1), above));
+
+        game.update(0.25);
+
+        let entity = game.entities.get(id).unwrap();
+        let previous = entity.previous_position;
+        let current = entity.body.position;
+        assert!(current.y < previous.y);
+        assert!((entity.interpolated_position(0.5) - previous.lerp(current, 0.5)).length() < 1e-4);
+    }
+
+    #[test]
+    fn waits_for_terrain_before_spawning() {
+        let mut game = Game::new();
+
+        game.tick();
+        assert_eq!(game.world_spawn, None);
+        let waiting = game.player.position;
+        game.tick();
+        assert_eq!(game.player.position, waiting);
+
+        while game.world_spawn.is_none() {
+            game.tick();
+        }
+        let spawn = game.world_spawn.unwrap();
+        assert_eq!(game.player.position, spawn);
+        assert_eq!(game.player.spawn_point, spawn);
+        for _ in 0..60 {
+            game.tick();
+        }
+        assert!(game.player.position.distance(spawn) < 0.1);
+    }
+
+    #[test]
+    fn breaking_blocks_drops_items() {
+        let mut game = game_with_empty_chunk();
+        game.terrain.set_block(ivec3(20, 40, 20), Block::GRASS);
+
+        game.break_block(ivec3(20, 40, 20));
+
+        assert_eq!(game.terrain.block(ivec3(20, 40, 20)), Block::AIR);
+        assert_eq!(game.entities.len(), 1);
+        let (_, entity) = game.entities.iter().next().unwrap();
+        assert_eq!(
+            entity.as_item().unwrap().stack,
+            ItemStack::new(Item::Block(Block::DIRT), 1)
+        );
+    }
+
+    #[test]
+    fn picks_up_nearby_items() {
+        let mut game = game_with_empty_chunk();
+        for x in 16..32 {
+            for z in 16..32 {
+                game.terrain.set_block(ivec3(x, 40, z), Block::STONE);
+            }
+        }
+        game.terrain.set_block(ivec3(21, 41, 20), Block::DIRT);
+        game.terrain.set_block(ivec3(30, 41, 30), Block::DIRT);
+        game.player.position = vec3(20.5, 41.0, 20.5);
+
+        game.break_block(ivec3(21, 41, 20));
+        game.break_block(ivec3(30, 41, 30));
+        for _ in 0..60 {
+            game.tick();
+        }
+
+        assert_eq!(
+            game.player.inventory.selected_stack(),
+            Some(ItemStack::new(Item::Block(Block::DIRT), 1))
+        );
+        let items = game
+            .entities
+            .iter()
+            .filter(|(_, entity)| entity.as_item().is_some());
+        assert_eq!(items.count(), 1);
+    }
+
+    #[test]
+    fn spectators_leave_items_alone() {
+        let mut game = game_with_empty_chunk();
+        game.player.movement_mode = MovementMode::Spectator;
+        game.player.position = vec3(20.5, 41.0, 20.5);
+        game.terrain.set_block(ivec3(21, 41, 20), Block::DIRT);
+
+        game.break_block(ivec3(21, 41, 20));
+        for _ in 0..60 {
+            game.tick();
+        }
+
+        assert_eq!(game.player.inventory.selected_stack(), None);
+    }
+
+    #[test]
+    fn placing_blocks_uses_up_the_selected_stack() {
+        let mut game = game_with_empty_chunk();
+        game.player.inventory.add(Item::Block(Block::STONE), 1);
+
+        assert!(game.place_block(ivec3(20, 40, 20), IVec3::Y));
+        assert!(!game.place_block(ivec3(21, 40, 20), IVec3::Y));
+
+        assert_eq!(game.terrain.block(ivec3(20, 40, 20)), Block::STONE);
+        assert_eq!(game.terrain.block(ivec3(21, 40, 20)), Block::AIR);
+    }
+
+    #[test]
+    fn blocks_are_not_placed_inside_mobs() {
+        let mut game = game_with_empty_chunk();
+        game.player.inventory.add(Item::Block(Block::STONE), 2);
+        game.entities
+            .spawn(Mob::entity(MobKind::Zombie, vec3(22.5, 41.0, 20.5)));
+        let item = game.entities.spawn(DroppedItem::entity(
+            ItemStack::new(Item::Apple, 1),
+            vec3(24.5, 41.0, 20.5),
+        ));
+
+        assert!(!game.place_block(ivec3(22, 41, 20), IVec3::Y));
+        assert_eq!(game.terrain.block(ivec3(22, 41, 20)), Block::AIR);
+
+        assert!(game.place_block(ivec3(24, 41, 20), IVec3::Y));
+        assert_eq!(game.entities.get(item).unwrap().body.position.y, 42.0);
+    }
+
+    #[test]
+    fn ladders_face_away_from_the_wall() {
+        let mut game = game_with_empty_chunk();
+        game.player.inventory.add(Item::Block(Block::LADDER), 2);
+
+        assert!(!game.place_block(ivec3(20, 41, 20), IVec3::Y));
+        assert!(game.place_block(ivec3(20, 41, 20), IVec3::NEG_X));
+        assert_eq!(
+            game.terrain.block(ivec3(20, 41, 20)),
+            Block::LADDER.with_facing(Facing::West)
+        );
+
+        game.break_block(ivec3(20, 41, 20));
+        let (_, entity) = game.entities.iter().next().unwrap();
+        assert_eq!(
+            entity.as_item().unwrap().stack.item,
+            Item::Block(Block::LADDER)
+        );
+    }
+
+    #[test]
+    fn vines_hang_on_walls_without_blocking_the_way() {
+        let mut game = game_with_empty_chunk();
+        game.player.inventory.add(Item::Block(Block::VINES), 1);
+
+        assert!(game.place_block(ivec3(20, 41, 20), IVec3::Z));
+        let vines = game.terrain.block(ivec3(20, 41, 20));
+        assert_eq!(vines, Block::VINES.with_facing(Facing::South));
+        assert!(vines.collision_box(ivec3(20, 41, 20)).is_none());
+        assert!(vines.shape(ivec3(20, 41, 20)).max.z - 20.0 < 0.5);
+    }
+
+    #[test]
+    fn mining_takes_time() {
+        let mut game = game_with_empty_chunk();
+        game.terrain.set_block(ivec3(20, 40, 20), Block::DIRT);
+
+        game.mining.set_target(Some(ivec3(20, 40, 20)));
+        for _ in 0..10 {
+            game.tick();
+        }
+        assert_eq!(game.terrain.block(ivec3(20, 40, 20)), Block::DIRT);
+        assert!(game.mining.progress() > 0.0);
+
+        for _ in 0..60 {
+            game.tick();
+        }
+        assert_eq!(game.terrain.block(ivec3(20, 40, 20)), Block::AIR);
+    }
+
+    #[test]
+    fn snow_can_be_dug_away() {
+        let mut game = game_with_empty_chunk();
+        game.terrain.set_block(ivec3(20, 40, 20), Block::SNOW);
+        let snow = game.terrain.block(ivec3(20, 40, 20));
+        assert!(snow.is_targetable());
+        assert_eq!(snow.shape(ivec3(20, 40, 20)).max.y, 40.0 + FLAT_THICKNESS);
+
+        game.mining.set_target(Some(ivec3(20, 40, 20)));
+        for _ in 0..10 {
+            game.tick();
+        }
+        assert_eq!(game.terrain.block(ivec3(20, 40, 20)), Block::AIR);
+    }
+
+    #[test]
+    fn creative_breaks_instantly_and_never_runs_out() {
+        let mut game = game_with_empty_chunk();
+        game.player.inventory.add(Item::Apple, 3);
+        game.set_game_mode(GameMode::Creative);
+        game.terrain.set_block(ivec3(20, 40, 20), Block::STONE);
+
+        game.mining.set_target(Some(ivec3(20, 40, 20)));
+        game.tick();
+        assert_eq!(game.terrain.block(ivec3(20, 40, 20)), Block::AIR);
+        assert_eq!(game.entities.len(), 0);
+
+        assert!(game.place_block(ivec3(20, 40, 20), IVec3::Y));
+        assert!(game.place_block(ivec3(21, 40, 20), IVec3::Y));
+        assert_eq!(
+            game.player.inventory.selected_stack(),
+            Some(ItemStack::new(Item::Block(Block::STONE), 1))
+        );
+
+        let health = game.player.health;
+        game.player.damage(5.0);
+        assert_eq!(game.player.health, health);
+        assert!(game.player.can_fly);
+
+        game.set_game_mode(GameMode::Survival);
+        assert!(!game.player.can_fly);
+        assert_eq!(
+            game.player.inventory.selected_stack(),
+            Some(ItemStack::new(Item::Apple, 3))
+        );
+        game.player.damage(5.0);
+        assert!(game.player.health < health);
+    }
+
+    #[test]
+    fn only_creative_players_fly() {
+        let mut game = game_with_empty_chunk();
+        for mode in [
+            MovementMode::Flying,
+            MovementMode::Noclip,
+            MovementMode::Spectator,
+        ] {
+            game.toggle_movement_mode(mode);
+            assert_eq!(game.player.movement_mode, MovementMode::Walking);
+        }
+
+        game.set_game_mode(GameMode::Creative);
+        game.toggle_movement_mode(MovementMode::Spectator);
+        assert_eq!(game.player.movement_mode, MovementMode::Spectator);
+
+        game.set_game_mode(GameMode::Survival);
+        assert_eq!(game.player.movement_mode, MovementMode::Walking);
+    }
+
+    #[test]
+    fn loading_settings_with_another_seed_starts_the_terrain_over() {
+        let mut game = game_with_empty_chunk();
+        game.load(&SaveData {
+            seed: 7,
+            game_mode: GameMode::Creative,
+        });
+
+        assert_eq!(game.terrain.seed, 7);
+        assert!(game.terrain.chunks.is_empty());
+        assert_eq!(game.world_spawn, None);
+        assert_eq!(game.save().game_mode, GameMode::Creative);
+    }
+
+    #[test]
+    fn changing_target_resets_progress() {
+        let mut game = game_with_empty_chunk();
+        game.terrain.set_block(ivec3(20, 40, 20), Block::DIRT);
+        game.terrain.set_block(ivec3(21, 40, 20), Block::DIRT);
+
+        game.mining.set_target(Some(ivec3(20, 40, 20)));
+        game.tick();
+        game.mining.set_target(Some(ivec3(21, 40, 20)));
+
+        assert_eq!(game.mining.progress(), 0.0);
+    }
+
+    #[test]
+    fn stone_mined_by_hand_drops_nothing() {
+        let mut game = game_with_empty_chunk();
+        game.terrain.set_block(ivec3(20, 40, 20), Block::STONE);
+
+        game.break_block(ivec3(20, 40, 20));
+
+        assert_eq!(game.player.inventory.selected_stack(), None);
+    }
+
+    #[test]
+    fn killing_mobs_drops_loot() {
+        let mut game = game_with_empty_chunk();
+        game.player.position = vec3(20.5, 41.0, 20.5);
+        let pig = game
+            .entities
+            .spawn(Mob::entity(MobKind::Pig, vec3(22.0, 41.0, 20.5)));
+
+        while game.entities.get(pig).is_some() {
+            assert!(game.attack(pig));
+            game.tick();
+        }
+
+        let (_, loot) = game.entities.iter().next().unwrap();
+        assert_eq!(loot.as_item().unwrap().stack.item, Item::RawMeat);
+    }
+
+    #[test]
+    fn distant_mobs_despawn() {
+        let mut game = game_with_empty_chunk();
+        game.player.position = vec3(19.5 - DESPAWN_DISTANCE, 41.0, 20.5);
+        game.entities
+            .spawn(Mob::entity(MobKind::Zombie, vec3(20.5, 41.0, 20.5)));
+
+        game.tick();
+
+        assert!(game.entities.iter().all(|(_, e)| e.as_mob().is_none()));
+    }
+
+    #[test]
+    fn lightning_hurts_things_nearby() {
+        let mut game = game_with_empty_chunk();
+        game.player.position = vec3(20.5, 41.0, 20.5);
+        let near = game
+            .entities
+            .spawn(Mob::entity(MobKind::Pig, vec3(22.0, 41.0, 20.5)));
+        let far = game
+            .entities
+            .spawn(Mob::entity(MobKind::Pig, vec3(28.0, 41.0, 20.5)));
+        let health = game.player.health;
+
+        game.strike_lightning(vec3(21.0, 41.0, 20.5));
+
+        assert!(game.player.health < health);
+        let mob_health = |id| game.entities.get(id).unwrap().as_mob().unwrap().health;
+        assert!(mob_health(near) < MobKind::Pig.max_health());
+        assert_eq!(mob_health(far), MobKind::Pig.max_health());
+        assert!(game.weather.lightning.is_some());
+    }
+
+    #[test]
+    fn storms_darken_the_sky() {
+        let mut game = Game::new();
+        let clear_light = game.sky_light();
+        let clear_brightness = game.brightness();
+
+        game.weather.set(WeatherKind::Thunder, 1000.0);
+        game.weather.update(60.0, &mut Random::with_seed(0));
+
+        assert!(game.sky_light() < clear_light);
+        assert!(game.brightness() < clear_brightness);
+    }
+
+    #[test]
+    fn snow_settles_on_cold_mountains() {
+        let mut game = game_with_empty_chunk();
+        // High enough up that it's freezing whatever the climate is below.
+        let peak = SEA_LEVEL + 150;
+        for x in 16..32 {
+            for z in 16..32 {
+                game.terrain.set_block(ivec3(x, peak, z), Block::STONE);
+            }
+        }
+        game.player.position = vec3(24.0, peak as f32 + 1.0, 24.0);
+        game.weather.set(WeatherKind::Rain, 1000.0);
+        game.weather.update(60.0, &mut Random::with_seed(0));
+
+        for _ in 0..1000 {
+            game.accumulate_snow();
+        }
+
+        let snowy = (16..32)
+            .flat_map(|x| (16..32).map(move |z| ivec3(x, peak + 1, z)))
+            .filter(|&p| game.terrain.block(p) == Block::SNOW)
+            .count();
+        assert!(snowy > 0);
+    }
+
+    #[test]
+    fn closing_crafting_gives_items_back() {
+        let mut game = game_with_empty_chunk();
+        game.player.inventory.add(Item::Bone, 2);
+        game.open_crafting();
+
+        game.click_crafting_slot(Slot::Inventory(0), Click::Stack);
+        game.click_crafting_slot(Slot::Grid(4), Click::Single);
+        game.close_crafting();
+
+        assert!(game.crafting.is_none());
+        assert_eq!(
+            game.player.inventory.slots[0],
+            Some(ItemStack::new(Item::Bone, 2))
+        );
+    }
+
+    #[test]
+    fn crafting_in_creative_leaves_the_palette_alone() {
+        let mut game = game_with_empty_chunk();
+        game.set_game_mode(GameMode::Creative);
+        let palette = game.player.inventory.slots;
+        game.open_crafting();
+
+        game.click_crafting_slot(Slot::Inventory(0), Click::Stack);
+        game.click_crafting_slot(Slot::Grid(4), Click::Stack);
+        // Picked up and put back again.
+        game.click_crafting_slot(Slot::Inventory(1), Click::Stack);
+        game.click_crafting_slot(Slot::Inventory(2), Click::Stack);
+        game.close_crafting();
 
-        self.player.update(delta_time);
-        self.player.collide_with_terrain(&self.terrain);
+        assert_eq!(game.player.inventory.slots, palette);
+        let (_, entity) = game.entities.iter().next().unwrap();
+        assert_eq!(entity.as_item().unwrap().stack, palette[0].unwrap());
+        assert_eq!(game.entities.iter().count(), 1);
     }
 }
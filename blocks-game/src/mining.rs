@@ -0,0 +1,72 @@
+use glam::IVec3;
+
+use crate::{
+    item::{self, Item},
+    terrain::Terrain,
+};
+
+/// Seconds between blocks broken instantly, so that holding the break button doesn't clear
+/// blocks as fast as the game ticks.
+const INSTANT_BREAK_DELAY: f32 = 0.25;
+
+/// Progress towards breaking the block that the player is holding the break button on.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Mining {
+    target: Option<IVec3>,
+    progress: f32,
+    /// Seconds until another block can be broken instantly.
+    cooldown: f32,
+}
+
+impl Mining {
+    pub fn target(&self) -> Option<IVec3> {
+        self.target
+    }
+
+    /// How close the target is to breaking, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+
+    /// Sets the block being mined, starting again from scratch if it has changed.
+    pub fn set_target(&mut self, target: Option<IVec3>) {
+        if target != self.target {
+            self.target = target;
+            self.progress = 0.0;
+        }
+    }
+
+    /// Mines the target for `delta_time` seconds, returning its position once it breaks.
+    pub fn update(
+        &mut self,
+        delta_time: f32,
+        terrain: &Terrain,
+        held: Option<Item>,
+    ) -> Option<IVec3> {
+        let target = self.target?;
+        let Some(break_time) = item::break_time(terrain.block(target), held) else {
+            self.progress = 0.0;
+            return None;
+        };
+
+        self.progress += delta_time / break_time;
+        if self.progress >= 1.0 {
+            self.progress = 0.0;
+            Some(target)
+        } else {
+            None
+        }
+    }
+
+    /// Breaks the target straight away, whatever it is and however hard, as long as it can be
+    /// broken at all. Returns its position if it breaks.
+    pub fn update_instant(&mut self, delta_time: f32, terrain: &Terrain) -> Option<IVec3> {
+        self.cooldown = (self.cooldown - delta_time).max(0.0);
+        let target = self.target?;
+        if self.cooldown > 0.0 || terrain.block(target).hardness().is_none() {
+            return None;
+        }
+        self.cooldown = INSTANT_BREAK_DELAY;
+        Some(target)
+    }
+}
@@ -0,0 +1,499 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use glam::{vec3, IVec3, Vec3, Vec3Swizzles};
+
+use crate::{
+    bounding_box::BoundingBox,
+    physics::{self, Body},
+    terrain::Terrain,
+};
+
+/// How many positions a search looks at before giving up, unless told otherwise.
+const DEFAULT_MAX_NODES: usize = 2000;
+const WALK_COST: u32 = 10;
+/// Extra cost per block jumped up.
+const CLIMB_COST: u32 = 10;
+/// Extra cost per block dropped down.
+const FALL_COST: u32 = 1;
+const SWIM_COST: u32 = 20;
+/// How close to the centre of a waypoint counts as having reached it.
+const ARRIVAL_DISTANCE: f32 = 0.3;
+const DIRECTIONS: [IVec3; 4] = [IVec3::X, IVec3::NEG_X, IVec3::Z, IVec3::NEG_Z];
+
+/// What an entity is able to do while following a path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathOptions {
+    /// Size of the entity along x and z.
+    pub width: f32,
+    pub height: f32,
+    /// The most blocks the entity can jump up in one step.
+    pub max_jump: i32,
+    /// The most blocks the entity is willing to drop down in one step.
+    pub max_fall: i32,
+    /// Whether the entity can move through liquids. Liquids are avoided entirely otherwise.
+    pub can_swim: bool,
+    /// The most positions to look at before settling for the closest one found.
+    pub max_nodes: usize,
+}
+
+impl PathOptions {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            max_jump: 1,
+            max_fall: 3,
+            can_swim: true,
+            max_nodes: DEFAULT_MAX_NODES,
+        }
+    }
+}
+
+/// A route through the world, as the block positions the entity's feet pass through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    /// Every position after the start, in order.
+    pub waypoints: Vec<IVec3>,
+    /// Whether the path ends at the goal, rather than as close to it as the search got.
+    pub complete: bool,
+}
+
+/// Finds the cheapest way to walk from `start` to `goal` with A*.
+///
+/// If the goal can't be reached within `options.max_nodes`, returns a path to the closest position
+/// found instead. Returns `None` if the entity can't stand at `start`.
+pub fn find_path(
+    terrain: &Terrain,
+    start: IVec3,
+    goal: IVec3,
+    options: &PathOptions,
+) -> Option<Path> {
+    let grid = Grid { terrain, options };
+    if !grid.can_stand(start) {
+        return None;
+    }
+
+    let mut open = BinaryHeap::new();
+    let mut costs = HashMap::from([(start, 0)]);
+    let mut came_from = HashMap::new();
+    let mut closest = (heuristic(start, goal), start);
+    open.push(Reverse((heuristic(start, goal), 0, start.to_array())));
+
+    let mut expanded = 0;
+    while let Some(Reverse((_, cost, node))) = open.pop() {
+        let node = IVec3::from_array(node);
+        if node == goal {
+            return Some(Path {
+                waypoints: reconstruct(&came_from, start, node),
+                complete: true,
+            });
+        }
+        if cost > costs[&node] {
+            continue;
+        }
+
+        expanded += 1;
+        if expanded > options.max_nodes {
+            break;
+        }
+
+        for (next, step_cost) in grid.neighbours(node) {
+            let next_cost = cost + step_cost;
+            if costs.get(&next).is_some_and(|&c| c <= next_cost) {
+                continue;
+            }
+            costs.insert(next, next_cost);
+            came_from.insert(next, node);
+
+            let estimate = heuristic(next, goal);
+            if estimate < closest.0 {
+                closest = (estimate, next);
+            }
+            open.push(Reverse((next_cost + estimate, next_cost, next.to_array())));
+        }
+    }
+
+    Some(Path {
+        waypoints: reconstruct(&came_from, start, closest.1),
+        complete: false,
+    })
+}
+
+/// A lower bound on the cost of getting from `from` to `to`, since every step moves one block
+/// horizontally and costs at least `WALK_COST`.
+fn heuristic(from: IVec3, to: IVec3) -> u32 {
+    (from.x.abs_diff(to.x) + from.z.abs_diff(to.z)) * WALK_COST
+}
+
+fn reconstruct(came_from: &HashMap<IVec3, IVec3>, start: IVec3, end: IVec3) -> Vec<IVec3> {
+    let mut waypoints = Vec::new();
+    let mut node = end;
+    while node != start {
+        waypoints.push(node);
+        node = came_from[&node];
+    }
+    waypoints.reverse();
+    waypoints
+}
+
+/// The world as seen by an entity of a particular size.
+struct Grid<'a> {
+    terrain: &'a Terrain,
+    options: &'a PathOptions,
+}
+
+impl Grid<'_> {
+    /// The space the entity takes up when standing in the middle of `node`.
+    fn bounding_box(&self, node: IVec3) -> BoundingBox {
+        let half_width = self.options.width / 2.0;
+        let center = node.as_vec3() + vec3(0.5, 0.0, 0.5);
+        BoundingBox::new(
+            center - vec3(half_width, 0.0, half_width),
+            center + vec3(half_width, self.options.height, half_width),
+        )
+    }
+
+    /// Whether the entity fits at `node`. Nowhere that the entity would poke into an unloaded
+    /// chunk is clear, since there's no telling what's in it.
+    fn is_clear(&self, node: IVec3) -> bool {
+        let bounding_box = self.bounding_box(node);
+        self.terrain.is_loaded(bounding_box)
+            && !physics::collides(self.terrain, bounding_box)
+            && (self.options.can_swim || !self.in_liquid(node))
+    }
+
+    fn in_liquid(&self, node: IVec3) -> bool {
+        self.terrain
+            .blocks_intersecting(self.bounding_box(node))
+            .any(|(_, b)| b.is_liquid())
+    }
+
+    fn has_floor(&self, node: IVec3) -> bool {
+        let bounding_box = self.bounding_box(node);
+        let below = BoundingBox::new(
+            vec3(
+                bounding_box.min.x,
+                bounding_box.min.y - 1.0,
+                bounding_box.min.z,
+            ),
+            vec3(bounding_box.max.x, bounding_box.min.y, bounding_box.max.z),
+        );
+        physics::collides(self.terrain, below)
+    }
+
+    /// Whether the entity can stay at `node` without falling.
+    fn can_stand(&self, node: IVec3) -> bool {
+        self.is_clear(node)
+            && (self.has_floor(node) || (self.options.can_swim && self.in_liquid(node)))
+    }
+
+    fn move_cost(&self, to: IVec3) -> u32 {
+        if self.in_liquid(to) {
+            SWIM_COST
+        } else {
+            WALK_COST
+        }
+    }
+
+    /// Every position reachable in one step from `node`, along with the cost of getting there.
+    fn neighbours(&self, node: IVec3) -> Vec<(IVec3, u32)> {
+        let mut neighbours = Vec::new();
+
+        for direction in DIRECTIONS {
+            let next = node + direction;
+            if self.is_clear(next) {
+                // Walk across, or drop down to the first place to stand.
+                for fall in 0..=self.options.max_fall {
+                    let below = next - fall * IVec3::Y;
+                    if fall > 0 && !self.is_clear(below) {
+                        break;
+                    }
+                    if self.can_stand(below) {
+                        let cost = self.move_cost(below) + fall as u32 * FALL_COST;
+                        neighbours.push((below, cost));
+                        break;
+                    }
+                }
+            } else {
+                // Jump up onto the first ledge with room to stand.
+                for climb in 1..=self.options.max_jump {
+                    if !self.is_clear(node + climb * IVec3::Y) {
+                        break;
+                    }
+                    let above = next + climb * IVec3::Y;
+                    if self.is_clear(above) {
+                        if self.can_stand(above) {
+                            let cost = self.move_cost(above) + climb as u32 * CLIMB_COST;
+                            neighbours.push((above, cost));
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+
+        if self.options.can_swim && self.in_liquid(node) {
+            let up = node + IVec3::Y;
+            if self.can_stand(up) {
+                neighbours.push((up, SWIM_COST));
+            }
+            let down = node - IVec3::Y;
+            if self.is_clear(down) && self.in_liquid(down) {
+                neighbours.push((down, SWIM_COST));
+            }
+        }
+
+        neighbours
+    }
+}
+
+/// Which way an entity following a path should move this tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Steering {
+    /// Horizontal unit vector towards the next waypoint, or zero if already above it.
+    pub direction: Vec3,
+    pub jump: bool,
+}
+
+/// Walks an entity along a [`Path`] one waypoint at a time.
+#[derive(Debug, Clone)]
+pub struct PathFollower {
+    pub path: Path,
+    next: usize,
+}
+
+impl PathFollower {
+    pub fn new(path: Path) -> Self {
+        Self { path, next: 0 }
+    }
+
+    pub fn next_waypoint(&self) -> Option<IVec3> {
+        self.path.waypoints.get(self.next).copied()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.path.waypoints.len()
+    }
+
+    /// Moves past any waypoints `body` has reached and steers it towards the next one. Returns
+    /// `None` once the end of the path is reached.
+    pub fn steer(&mut self, body: &Body, terrain: &Terrain) -> Option<Steering> {
+        while let Some(waypoint) = self.next_waypoint() {
+            let target = waypoint.as_vec3() + vec3(0.5, 0.0, 0.5);
+            let offset = target - body.position;
+            let arrived = offset.xz().length() < ARRIVAL_DISTANCE && offset.y.abs() < 0.5;
+            if !arrived {
+                let climbing = offset.y > 0.5 || (offset.y > 0.0 && body.in_liquid(terrain));
+                return Some(Steering {
+                    direction: vec3(offset.x, 0.0, offset.z).normalize_or_zero(),
+                    jump: climbing && (body.on_ground || body.in_liquid(terrain)),
+                });
+            }
+            self.next += 1;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{ivec3, vec3};
+
+    use super::{find_path, PathFollower, PathOptions};
+    use crate::{
+        physics::Body,
+        terrain::{
+            block::{Block, Facing},
+            Terrain,
+        },
+    };
+
+    const FLOOR: i32 = 10;
+
+    /// Two chunks side by side along x with a stone floor.
+    fn flat_terrain() -> Terrain {
+        Terrain::with_floor(0..=1, 0..=0, FLOOR, Block::STONE)
+    }
+
+    fn options() -> PathOptions {
+        PathOptions::new(0.8, 1.8)
+    }
+
+    #[test]
+    fn walks_across_chunks() {
+        let terrain = flat_terrain();
+
+        let path = find_path(
+            &terrain,
+            ivec3(2, FLOOR + 1, 2),
+            ivec3(28, FLOOR + 1, 2),
+            &options(),
+        )
+        .unwrap();
+
+        assert!(path.complete);
+        assert_eq!(path.waypoints.len(), 26);
+        assert_eq!(path.waypoints.last(), Some(&ivec3(28, FLOOR + 1, 2)));
+    }
+
+    #[test]
+    fn keeps_out_of_unloaded_chunks() {
+        let terrain = flat_terrain();
+        let options = PathOptions::new(1.8, 1.8);
+
+        // Standing on the last column of the loaded chunks would put half of the body past them.
+        let path = find_path(
+            &terrain,
+            ivec3(28, FLOOR + 1, 2),
+            ivec3(31, FLOOR + 1, 2),
+            &options,
+        )
+        .unwrap();
+
+        assert!(!path.complete);
+        assert!(path.waypoints.iter().all(|p| p.x < 31));
+    }
+
+    #[test]
+    fn goes_around_walls_too_tall_to_jump() {
+        let mut terrain = flat_terrain();
+        for z in 0..10 {
+            for y in 1..=2 {
+                terrain.set_block(ivec3(5, FLOOR + y, z), Block::STONE);
+            }
+        }
+
+        let path = find_path(
+            &terrain,
+            ivec3(2, FLOOR + 1, 2),
+            ivec3(8, FLOOR + 1, 2),
+            &options(),
+        )
+        .unwrap();
+
+        assert!(path.complete);
+        assert!(path.waypoints.iter().any(|p| p.z >= 10));
+        assert!(path.waypoints.iter().all(|p| p.y == FLOOR + 1));
+    }
+
+    #[test]
+    fn goes_around_ladders() {
+        let mut terrain = flat_terrain();
+        for z in 0..10 {
+            for y in 1..=2 {
+                let ladder = Block::LADDER.with_facing(Facing::West);
+                terrain.set_block(ivec3(5, FLOOR + y, z), ladder);
+            }
+        }
+
+        let path = find_path(
+            &terrain,
+            ivec3(2, FLOOR + 1, 2),
+            ivec3(8, FLOOR + 1, 2),
+            &options(),
+        )
+        .unwrap();
+
+        assert!(path.complete);
+        assert!(path.waypoints.iter().any(|p| p.z >= 10));
+    }
+
+    #[test]
+    fn jumps_onto_ledges() {
+        let mut terrain = flat_terrain();
+        for x in 5..10 {
+            for z in 0..16 {
+                terrain.set_block(ivec3(x, FLOOR + 1, z), Block::STONE);
+            }
+        }
+
+        let path = find_path(
+            &terrain,
+            ivec3(2, FLOOR + 1, 2),
+            ivec3(7, FLOOR + 2, 2),
+            &options(),
+        )
+        .unwrap();
+
+        assert!(path.complete);
+        assert!(path.waypoints.contains(&ivec3(5, FLOOR + 2, 2)));
+    }
+
+    #[test]
+    fn avoids_liquids_when_it_cannot_swim() {
+        let mut terrain = flat_terrain();
+        for x in 4..7 {
+            for z in 0..12 {
+                terrain.set_block(ivec3(x, FLOOR, z), Block::WATER);
+            }
+        }
+        let options = PathOptions {
+            can_swim: false,
+            ..options()
+        };
+
+        let path = find_path(
+            &terrain,
+            ivec3(2, FLOOR + 1, 2),
+            ivec3(9, FLOOR + 1, 2),
+            &options,
+        )
+        .unwrap();
+
+        assert!(path.complete);
+        assert!(path.waypoints.iter().any(|p| p.z >= 12));
+    }
+
+    #[test]
+    fn gives_up_after_the_search_budget() {
+        let terrain = flat_terrain();
+        let options = PathOptions {
+            max_nodes: 10,
+            ..options()
+        };
+
+        let path = find_path(
+            &terrain,
+            ivec3(2, FLOOR + 1, 2),
+            ivec3(28, FLOOR + 1, 2),
+            &options,
+        )
+        .unwrap();
+
+        assert!(!path.complete);
+        assert!(!path.waypoints.is_empty());
+    }
+
+    #[test]
+    fn follower_reaches_the_end() {
+        let mut terrain = flat_terrain();
+        terrain.set_block(ivec3(6, FLOOR + 1, 2), Block::STONE);
+        let path = find_path(
+            &terrain,
+            ivec3(2, FLOOR + 1, 2),
+            ivec3(8, FLOOR + 1, 2),
+            &options(),
+        )
+        .unwrap();
+        let mut follower = PathFollower::new(path);
+        let mut body = Body::new(vec3(2.5, FLOOR as f32 + 1.0, 2.5), 0.8, 1.8);
+
+        for _ in 0..600 {
+            let Some(steering) = follower.steer(&body, &terrain) else {
+                break;
+            };
+            body.velocity.x = steering.direction.x * 4.0;
+            body.velocity.z = steering.direction.z * 4.0;
+            if steering.jump {
+                body.velocity.y = 7.5;
+            }
+            body.update(1.0 / 60.0, &terrain);
+        }
+
+        assert!(follower.is_finished());
+        assert_eq!(body.position.floor().as_ivec3(), ivec3(8, FLOOR + 1, 2));
+    }
+}
@@ -0,0 +1,212 @@
+use glam::{vec3, BVec3, Vec3, Vec3Swizzles};
+
+use crate::{bounding_box::BoundingBox, terrain::Terrain};
+
+pub const GRAVITY: f32 = 20.0;
+/// Gravity while in a liquid, which is lower because of buoyancy.
+pub const LIQUID_GRAVITY: f32 = 4.0;
+/// How quickly vertical movement slows down in a liquid, as a fraction per second.
+pub const LIQUID_DRAG: f32 = 2.0;
+/// The longest time step that is simulated at once, which keeps jumps and falls consistent at low
+/// frame rates.
+pub const MAX_SUBSTEP: f32 = 0.01;
+
+/// Splits `delta_time` into equal steps no longer than `MAX_SUBSTEP`, returning how many steps
+/// there are and how long each one is.
+pub fn substeps(delta_time: f32) -> (u32, f32) {
+    let substeps = (delta_time / MAX_SUBSTEP).ceil().max(1.0);
+    (substeps as u32, delta_time / substeps)
+}
+
+/// The collision boxes of every block touching `area`.
+pub fn solid_blocks(terrain: &Terrain, area: BoundingBox) -> Vec<BoundingBox> {
+    terrain
+        .blocks_intersecting(area)
+        .filter_map(|(p, b)| b.collision_box(p))
+        .collect()
+}
+
+/// Whether the collision box of any block overlaps `area`.
+pub fn collides(terrain: &Terrain, area: BoundingBox) -> bool {
+    solid_blocks(terrain, area)
+        .iter()
+        .any(|collision| collision.intersects(&area))
+}
+
+pub fn in_liquid(terrain: &Terrain, bounding_box: BoundingBox) -> bool {
+    terrain
+        .blocks_intersecting(bounding_box)
+        .any(|(_, b)| b.is_liquid())
+}
+
+/// Where a box ends up after moving through the terrain, as worked out by `collide`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Movement {
+    pub moved: Vec3,
+    /// Whether the box ended up standing on something.
+    pub landed: bool,
+    /// The axes along which the box was stopped short.
+    pub blocked: BVec3,
+    /// Whether the box was stopped by a ledge that it could jump onto, in which case it doesn't
+    /// move at all.
+    pub can_jump: bool,
+}
+
+impl Movement {
+    /// `velocity` with the parts along blocked axes removed.
+    pub fn stop_blocked(&self, velocity: Vec3) -> Vec3 {
+        Vec3::select(self.blocked, Vec3::ZERO, velocity)
+    }
+}
+
+/// Moves `bounding_box` by up to `displacement`, stopping at solid blocks. A box that was
+/// `on_ground`, or lands during the move, steps up ledges no taller than `step_height`, and
+/// ledges up to `jump_height` are reported as jumpable instead.
+pub fn collide(
+    terrain: &Terrain,
+    bounding_box: BoundingBox,
+    displacement: Vec3,
+    on_ground: bool,
+    step_height: f32,
+    jump_height: f32,
+) -> Movement {
+    let obstacles = solid_blocks(
+        terrain,
+        bounding_box
+            .expand_towards(displacement)
+            .expand_towards(step_height.max(jump_height) * Vec3::Y),
+    );
+
+    let mut moved = bounding_box.sweep(displacement, &obstacles);
+    let mut landed = displacement.y < 0.0 && moved.y != displacement.y;
+
+    let blocked = moved.x != displacement.x || moved.z != displacement.z;
+    if blocked && (on_ground || landed) {
+        if let Some((stepped, stepped_landed)) =
+            step_up(bounding_box, displacement, step_height, &obstacles)
+                .filter(|&(stepped, _)| further(stepped, moved))
+        {
+            moved = stepped;
+            landed = stepped_landed;
+        } else if step_up(bounding_box, displacement, jump_height, &obstacles)
+            .is_some_and(|(stepped, _)| further(stepped, moved))
+        {
+            return Movement {
+                moved: Vec3::ZERO,
+                landed: on_ground,
+                blocked: BVec3::FALSE,
+                can_jump: true,
+            };
+        }
+    }
+
+    Movement {
+        moved,
+        landed,
+        blocked: moved.cmpne(displacement),
+        can_jump: false,
+    }
+}
+
+/// Tries moving `bounding_box` horizontally after lifting it by up to `height`, then drops it
+/// back down. Returns the overall movement and whether the box landed on something.
+fn step_up(
+    bounding_box: BoundingBox,
+    displacement: Vec3,
+    height: f32,
+    obstacles: &[BoundingBox],
+) -> Option<(Vec3, bool)> {
+    if height <= 0.0 {
+        return None;
+    }
+
+    let lifted = bounding_box.sweep(height * Vec3::Y, obstacles);
+    let horizontal = vec3(displacement.x, 0.0, displacement.z);
+    let stepped = (bounding_box + lifted).sweep(horizontal, obstacles);
+    let drop = displacement.y.min(0.0) - lifted.y;
+    let dropped = (bounding_box + lifted + stepped).sweep(drop * Vec3::Y, obstacles);
+
+    Some((lifted + stepped + dropped, dropped.y != drop))
+}
+
+/// Whether `a` covers more horizontal distance than `b`.
+fn further(a: Vec3, b: Vec3) -> bool {
+    a.xz().length_squared() > b.xz().length_squared() + BoundingBox::EPSILON
+}
+
+/// An axis-aligned box that falls under gravity and collides with terrain, shared by everything
+/// that moves around the world other than the player.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Body {
+    /// Centre of the bottom face.
+    pub position: Vec3,
+    pub velocity: Vec3,
+    /// Size of the box along x and z.
+    pub width: f32,
+    pub height: f32,
+    pub on_ground: bool,
+    /// The tallest ledge that the body walks up without jumping.
+    pub step_height: f32,
+    /// How quickly horizontal movement slows down on the ground, as a fraction per second.
+    pub ground_friction: f32,
+}
+
+impl Body {
+    pub fn new(position: Vec3, width: f32, height: f32) -> Self {
+        Self {
+            position,
+            velocity: Vec3::ZERO,
+            width,
+            height,
+            on_ground: false,
+            step_height: 0.0,
+            ground_friction: 0.0,
+        }
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox {
+        let half_width = self.width / 2.0;
+        BoundingBox::new(
+            self.position - vec3(half_width, 0.0, half_width),
+            self.position + vec3(half_width, self.height, half_width),
+        )
+    }
+
+    pub fn in_liquid(&self, terrain: &Terrain) -> bool {
+        in_liquid(terrain, self.bounding_box())
+    }
+
+    /// Applies gravity and friction and moves the body for `delta_time` seconds.
+    pub fn update(&mut self, delta_time: f32, terrain: &Terrain) {
+        let (substeps, substep_time) = substeps(delta_time);
+        for _ in 0..substeps {
+            if self.in_liquid(terrain) {
+                self.velocity.y -= LIQUID_GRAVITY * substep_time;
+                self.velocity.y *= (-LIQUID_DRAG * substep_time).exp();
+            } else {
+                self.velocity.y -= GRAVITY * substep_time;
+            }
+            if self.on_ground {
+                let friction = (-self.ground_friction * substep_time).exp();
+                self.velocity.x *= friction;
+                self.velocity.z *= friction;
+            }
+            self.move_with_collision(self.velocity * substep_time, terrain);
+        }
+    }
+
+    /// Moves by up to `displacement`, stopping at solid blocks and stepping up low ledges.
+    pub fn move_with_collision(&mut self, displacement: Vec3, terrain: &Terrain) {
+        let movement = collide(
+            terrain,
+            self.bounding_box(),
+            displacement,
+            self.on_ground,
+            self.step_height,
+            0.0,
+        );
+        self.position += movement.moved;
+        self.on_ground = movement.landed;
+        self.velocity = movement.stop_blocked(self.velocity);
+    }
+}
@@ -1,17 +1,87 @@
-use glam::{vec3, IVec3, Quat, Vec2, Vec3};
+use glam::{vec3, Quat, Vec2, Vec3, Vec3Swizzles};
 
 use crate::{
     bounding_box::BoundingBox,
+    hunger::{Hunger, MAX_FOOD},
+    inventory::Inventory,
+    physics::{self, GRAVITY, LIQUID_DRAG, LIQUID_GRAVITY},
     terrain::{block::Block, Terrain},
-    util::TotalOrd,
 };
 
-const GRAVITY: f32 = 20.0;
 const JUMP_VELOCITY: f32 = 10.0;
 const WALK_SPEED: f32 = 5.0;
 const SPRINT_SPEED: f32 = 10.0;
-/// The amount of overlap past which a collision will not be resolved.
-const OVERLAP_THRESHOLD: f32 = 0.5;
+const FLY_SPEED: f32 = 10.0;
+const FLY_SPRINT_SPEED: f32 = 20.0;
+const FLY_VERTICAL_SPEED: f32 = 8.0;
+const SNEAK_SPEED: f32 = 1.5;
+const SWIM_SPEED: f32 = 2.5;
+const HEIGHT: f32 = 2.75;
+const SNEAK_HEIGHT: f32 = 2.25;
+const EYE_HEIGHT: f32 = 2.5;
+const SNEAK_EYE_HEIGHT: f32 = 2.0;
+/// How far below the player's feet to look for something to stand on when sneaking.
+const EDGE_CHECK_DEPTH: f32 = 0.1;
+/// How much to shorten a step that would walk off an edge at a time.
+const EDGE_CHECK_STEP: f32 = 0.05;
+/// Upwards acceleration while holding jump in a liquid.
+const SWIM_UP_ACCELERATION: f32 = 16.0;
+/// How many seconds the player can hold its breath under water.
+const MAX_OXYGEN: f32 = 15.0;
+/// How many seconds of breath are regained per second out of water.
+const OXYGEN_REFILL_RATE: f32 = 5.0;
+pub const MAX_HEALTH: f32 = 20.0;
+/// The fastest the player can hit the ground without being hurt, which is about a six block fall.
+const SAFE_LANDING_SPEED: f32 = 15.0;
+/// Damage per unit of landing speed above `SAFE_LANDING_SPEED`.
+const FALL_DAMAGE: f32 = 1.0;
+/// Damage per second while the player's head is inside a solid block.
+const SUFFOCATION_DAMAGE: f32 = 2.0;
+/// Damage per second while the player is out of oxygen.
+const DROWNING_DAMAGE: f32 = 2.0;
+/// Health regained per second while well fed.
+const REGENERATION_RATE: f32 = 0.5;
+/// Exhaustion for each point of health regenerated.
+const REGENERATION_EXHAUSTION: f32 = 6.0;
+/// Exhaustion for each block travelled while sprinting.
+const SPRINT_EXHAUSTION: f32 = 0.1;
+const JUMP_EXHAUSTION: f32 = 0.05;
+const SPRINT_JUMP_EXHAUSTION: f32 = 0.2;
+/// Exhaustion for each point of damage taken.
+const DAMAGE_EXHAUSTION: f32 = 0.1;
+/// Pressing jump twice within this many seconds toggles flight.
+const DOUBLE_TAP_TIME: f32 = 0.3;
+/// Tall enough to walk up a single block.
+const STEP_HEIGHT: f32 = 1.0;
+/// The highest ledge that a jump can reach.
+const AUTO_JUMP_HEIGHT: f32 = JUMP_VELOCITY * JUMP_VELOCITY / (2.0 * GRAVITY);
+/// How fast the player moves up and down ladders and vines.
+const CLIMB_SPEED: f32 = 3.0;
+/// How far around the player the terrain has to be generated for it to move.
+const LOADED_MARGIN: f32 = 1.0;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MovementMode {
+    /// Affected by gravity and collides with terrain.
+    #[default]
+    Walking,
+    /// Creative flight: no gravity, and `walk_vector.y` moves up and down.
+    Flying,
+    /// Like flying, but passes through terrain.
+    Noclip,
+    /// Like noclip, but unable to interact with the world.
+    Spectator,
+}
+
+impl MovementMode {
+    pub fn is_flying(self) -> bool {
+        self != Self::Walking
+    }
+
+    pub fn collides(self) -> bool {
+        matches!(self, Self::Walking | Self::Flying)
+    }
+}
 
 #[derive(Default)]
 pub struct Player {
@@ -21,25 +91,67 @@ pub struct Player {
     pub on_ground: bool,
     pub walk_vector: Vec3,
     pub sprinting: bool,
+    /// Whether sneak is held. Sneaking only has an effect while walking.
+    pub sneaking: bool,
+    /// The tallest ledge that the player walks up without jumping.
+    pub step_height: f32,
+    /// Whether to jump automatically when walking into a ledge that is too tall to step up.
+    pub auto_jump: bool,
+    pub movement_mode: MovementMode,
+    /// Whether double-tapping jump toggles flight.
+    pub can_fly: bool,
+    /// Whether the player can't be hurt and doesn't get hungry.
+    pub invulnerable: bool,
+    time_since_jump: Option<f32>,
+    /// Seconds of breath left before the player starts drowning.
+    pub oxygen: f32,
+    pub health: f32,
+    pub hunger: Hunger,
+    pub inventory: Inventory,
+    dead: bool,
+    /// Where the player reappears after dying.
+    pub spawn_point: Vec3,
 }
 
 impl Player {
     pub fn new() -> Self {
+        let spawn_point = vec3(0.0, 80.0, 0.0);
         Self {
-            position: vec3(0.0, 80.0, 0.0),
+            position: spawn_point,
+            step_height: STEP_HEIGHT,
+            can_fly: true,
+            oxygen: MAX_OXYGEN,
+            health: MAX_HEALTH,
+            spawn_point,
             ..Default::default()
         }
     }
 
     pub fn bounding_box(&self) -> BoundingBox {
+        let height = if self.is_sneaking() {
+            SNEAK_HEIGHT
+        } else {
+            HEIGHT
+        };
         BoundingBox::new(
             self.position + vec3(-0.875, 0.0, -0.875),
-            self.position + vec3(0.875, 2.75, 0.875),
+            self.position + vec3(0.875, height, 0.875),
         )
     }
 
     pub fn head_position(&self) -> Vec3 {
-        self.position + vec3(0.0, 2.5, 0.0)
+        let eye_height = if self.is_sneaking() {
+            SNEAK_EYE_HEIGHT
+        } else {
+            EYE_HEIGHT
+        };
+        self.position + vec3(0.0, eye_height, 0.0)
+    }
+
+    /// Whether the player is crouched. Sneak only has an effect while walking; while flying it
+    /// just descends.
+    pub fn is_sneaking(&self) -> bool {
+        self.sneaking && self.movement_mode == MovementMode::Walking
     }
 
     pub fn looking_direction(&self) -> Vec3 {
@@ -62,31 +174,226 @@ impl Player {
         )
     }
 
-    pub fn update(&mut self, delta_time: f32) {
+    /// Whether the player can break and place blocks and pick up items.
+    pub fn can_interact(&self) -> bool {
+        !self.dead && self.movement_mode != MovementMode::Spectator
+    }
+
+    /// Switches to `mode`, or back to walking if the player is already in that mode.
+    pub fn toggle_movement_mode(&mut self, mode: MovementMode) {
+        self.movement_mode = if self.movement_mode == mode {
+            MovementMode::Walking
+        } else {
+            mode
+        };
+        self.velocity.y = 0.0;
+    }
+
+    /// Whether the player is actually sprinting. Flying is always allowed to sprint, but walking
+    /// needs enough food.
+    pub fn is_sprinting(&self) -> bool {
+        self.sprinting && (self.movement_mode.is_flying() || self.hunger.can_sprint())
+    }
+
+    pub fn update(&mut self, delta_time: f32, terrain: &Terrain) {
         self.constrain_head_angle();
 
+        if self.dead || !self.surroundings_loaded(delta_time, terrain) {
+            return;
+        }
+
+        if let Some(time) = &mut self.time_since_jump {
+            *time += delta_time;
+        }
+
+        let flying = self.movement_mode.is_flying();
+        let swimming = !flying && self.in_liquid(terrain);
         let walk_rotation = Quat::from_rotation_y(self.head_angle.y.to_radians());
         let walk_velocity = walk_rotation
-            * self.walk_vector
-            * if self.sprinting {
-                SPRINT_SPEED
-            } else {
-                WALK_SPEED
+            * vec3(self.walk_vector.x, 0.0, self.walk_vector.z)
+            * match (flying, swimming, self.is_sprinting()) {
+                (true, _, false) => FLY_SPEED,
+                (true, _, true) => FLY_SPRINT_SPEED,
+                _ if self.is_sneaking() => SNEAK_SPEED,
+                (false, true, _) => SWIM_SPEED,
+                (false, false, false) => WALK_SPEED,
+                (false, false, true) => SPRINT_SPEED,
             };
         self.velocity.x = walk_velocity.x;
         self.velocity.z = walk_velocity.z;
+        if flying {
+            self.velocity.y = self.walk_vector.y * FLY_VERTICAL_SPEED;
+        }
+
+        if self.movement_mode.collides() && self.head_in_liquid(terrain) {
+            self.oxygen = (self.oxygen - delta_time).max(0.0);
+        } else {
+            self.oxygen = (self.oxygen + OXYGEN_REFILL_RATE * delta_time).min(MAX_OXYGEN);
+        }
+        if self.oxygen == 0.0 {
+            self.damage(DROWNING_DAMAGE * delta_time);
+        }
+        if self.movement_mode.collides() && self.head_in_solid(terrain) {
+            self.damage(SUFFOCATION_DAMAGE * delta_time);
+        }
+        if self.hunger.can_regenerate() && !self.dead && self.health < MAX_HEALTH {
+            let healed = (REGENERATION_RATE * delta_time).min(MAX_HEALTH - self.health);
+            self.health += healed;
+            self.exhaust(REGENERATION_EXHAUSTION * healed);
+        }
+
+        let start_position = self.position;
+
+        let (substeps, substep_time) = physics::substeps(delta_time);
+        for _ in 0..substeps {
+            if !self.movement_mode.is_flying() {
+                if self.in_liquid(terrain) {
+                    self.velocity.y -= LIQUID_GRAVITY * substep_time;
+                    if self.walk_vector.y > 0.0 {
+                        self.velocity.y += SWIM_UP_ACCELERATION * substep_time;
+                    }
+                    self.velocity.y *= (-LIQUID_DRAG * substep_time).exp();
+                } else if self.is_climbing(terrain) {
+                    self.velocity.y = self.climb_velocity(walk_velocity, terrain);
+                } else {
+                    self.velocity.y -= GRAVITY * substep_time;
+                }
+            }
+
+            if self.movement_mode.collides() {
+                self.move_with_collision(self.velocity * substep_time, terrain);
+            } else {
+                self.position += self.velocity * substep_time;
+                self.on_ground = false;
+            }
+
+            // Flying down onto the ground lands.
+            if self.movement_mode == MovementMode::Flying && self.on_ground {
+                self.movement_mode = MovementMode::Walking;
+            }
+        }
 
-        self.velocity.y -= GRAVITY * delta_time;
+        if self.movement_mode == MovementMode::Walking && self.is_sprinting() {
+            let distance = (self.position - start_position).xz().length();
+            self.exhaust(SPRINT_EXHAUSTION * distance);
+        }
+    }
 
-        self.position += self.velocity * delta_time;
+    /// Whether the terrain the player could move into this update has been generated. Terrain
+    /// that hasn't reads as air, so the player waits rather than falling through the world.
+    /// Players that pass through blocks don't need to wait.
+    pub fn surroundings_loaded(&self, delta_time: f32, terrain: &Terrain) -> bool {
+        let reach = self
+            .bounding_box()
+            .expand_towards(self.velocity * delta_time)
+            .expand_by(LOADED_MARGIN);
+        !self.movement_mode.collides() || terrain.is_loaded(reach)
     }
 
-    fn constrain_head_angle(&mut self) {
-        if self.head_angle.x > 90.0 {
-            self.head_angle.x = 90.0;
-        } else if self.head_angle.x < -90.0 {
-            self.head_angle.x = -90.0;
+    /// Whether the player is on a ladder or vines, so it climbs rather than falls.
+    pub fn is_climbing(&self, terrain: &Terrain) -> bool {
+        self.movement_mode.collides() && self.climbable_blocks(terrain).next().is_some()
+    }
+
+    /// The ladders and vines that the player is in.
+    fn climbable_blocks<'a>(&self, terrain: &'a Terrain) -> impl Iterator<Item = Block> + 'a {
+        terrain
+            .blocks_intersecting(self.bounding_box().expand_by(-BoundingBox::EPSILON))
+            .map(|(_, b)| b)
+            .filter(|b| b.is_climbable())
+    }
+
+    /// Vertical velocity while climbing. Jumping or walking towards the wall that a ladder or
+    /// vines hang on climbs up, sneaking holds on and otherwise the player slides down.
+    fn climb_velocity(&self, walk_velocity: Vec3, terrain: &Terrain) -> f32 {
+        let towards_wall = self.climbable_blocks(terrain).any(|block| {
+            block.facing().is_some_and(|facing| {
+                // Climbable blocks face away from the wall behind them.
+                walk_velocity.dot(facing.direction().as_vec3()) < 0.0
+            })
+        });
+        if self.sneaking {
+            0.0
+        } else if self.walk_vector.y > 0.0 || towards_wall {
+            CLIMB_SPEED
+        } else {
+            -CLIMB_SPEED
         }
+    }
+
+    /// Whether any part of the player is in a liquid.
+    pub fn in_liquid(&self, terrain: &Terrain) -> bool {
+        physics::in_liquid(terrain, self.bounding_box())
+    }
+
+    /// Whether the player's head is in a liquid, so it can't breathe.
+    pub fn head_in_liquid(&self, terrain: &Terrain) -> bool {
+        terrain
+            .block(self.head_position().floor().as_ivec3())
+            .is_liquid()
+    }
+
+    /// Whether the player's head is inside a solid block, so it is suffocating.
+    pub fn head_in_solid(&self, terrain: &Terrain) -> bool {
+        terrain
+            .block(self.head_position().floor().as_ivec3())
+            .is_solid()
+    }
+
+    /// How much breath the player has left, from 0 to 1.
+    pub fn oxygen_fraction(&self) -> f32 {
+        self.oxygen / MAX_OXYGEN
+    }
+
+    /// How much health the player has left, from 0 to 1.
+    pub fn health_fraction(&self) -> f32 {
+        self.health / MAX_HEALTH
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.dead
+    }
+
+    pub fn damage(&mut self, amount: f32) {
+        if self.dead || self.invulnerable || amount <= 0.0 {
+            return;
+        }
+        self.health = (self.health - amount).max(0.0);
+        self.exhaust(DAMAGE_EXHAUSTION * amount);
+        if self.health == 0.0 {
+            self.dead = true;
+            self.velocity = Vec3::ZERO;
+            self.walk_vector = Vec3::ZERO;
+        }
+    }
+
+    /// Makes the player hungrier, unless it's invulnerable.
+    fn exhaust(&mut self, amount: f32) {
+        if !self.invulnerable {
+            self.hunger.exhaust(amount);
+        }
+    }
+
+    /// Brings the player back to life at its spawn point.
+    pub fn respawn(&mut self) {
+        self.dead = false;
+        self.health = MAX_HEALTH;
+        self.oxygen = MAX_OXYGEN;
+        self.hunger = Hunger::new();
+        self.position = self.spawn_point;
+        self.velocity = Vec3::ZERO;
+        self.movement_mode = MovementMode::Walking;
+    }
+
+    /// Turns the head by `delta` degrees of pitch and yaw. This happens straight away rather
+    /// than on the next tick, so that looking around stays smooth whatever the tick rate.
+    pub fn turn_head(&mut self, delta: Vec2) {
+        self.head_angle += delta;
+        self.constrain_head_angle();
+    }
+
+    fn constrain_head_angle(&mut self) {
+        self.head_angle.x = self.head_angle.x.clamp(-90.0, 90.0);
 
         self.head_angle.y %= 360.0;
         if self.head_angle.y > 180.0 {
@@ -96,62 +403,918 @@ impl Player {
         }
     }
 
-    pub fn collide_with_terrain(&mut self, terrain: &Terrain) {
-        self.on_ground = false;
+    /// Moves the player by `displacement`, stopping at any solid blocks in the way and climbing
+    /// up ledges no taller than `step_height`.
+    fn move_with_collision(&mut self, displacement: Vec3, terrain: &Terrain) {
+        let displacement = if self.is_sneaking() && self.on_ground {
+            self.avoid_edges(displacement, terrain)
+        } else {
+            displacement
+        };
 
-        for (block_pos, _) in terrain
-            .blocks_intersecting(self.bounding_box())
-            .filter(|&(_, b)| b != Block::AIR)
-        {
-            self.collide_with_block(block_pos, terrain);
-        }
-    }
-
-    /// Moves the player by the smallest amount necessary to not collide with
-    /// the block at `block_pos`. This method assumes that the player currently
-    /// intersects the block.
-    fn collide_with_block(&mut self, block_pos: IVec3, terrain: &Terrain) {
-        let block = BoundingBox::new(block_pos.as_vec3(), block_pos.as_vec3() + Vec3::ONE);
-        let player = self.bounding_box();
-
-        // Top, bottom, etc. are faces of the block
-        let west = block.max.x - player.min.x;
-        let east = player.max.x - block.min.x;
-        let top = block.max.y - player.min.y;
-        let bottom = player.max.y - block.min.y;
-        let north = block.max.z - player.min.z;
-        let south = player.max.z - block.min.z;
-
-        if let Some((depth, direction)) = [
-            (west, Vec3::X),
-            (east, -Vec3::X),
-            (top, Vec3::Y),
-            (bottom, -Vec3::Y),
-            (north, Vec3::Z),
-            (south, -Vec3::Z),
-        ]
-        .into_iter()
-        .filter(|&(d, _)| d < OVERLAP_THRESHOLD)
-        .filter(|(_, v)| terrain.block(block_pos + v.as_ivec3()) == Block::AIR)
-        .min_by_key(|&(d, _)| TotalOrd(d))
+        let jump_height = if self.auto_jump {
+            AUTO_JUMP_HEIGHT
+        } else {
+            0.0
+        };
+        let movement = physics::collide(
+            terrain,
+            self.bounding_box(),
+            displacement,
+            self.on_ground,
+            self.step_height,
+            jump_height,
+        );
+        if movement.can_jump {
+            self.launch();
+            return;
+        }
+
+        if movement.landed && self.movement_mode == MovementMode::Walking {
+            self.damage((-self.velocity.y - SAFE_LANDING_SPEED) * FALL_DAMAGE);
+        }
+
+        self.position += movement.moved;
+        self.on_ground = movement.landed;
+        self.velocity = movement.stop_blocked(self.velocity);
+    }
+
+    /// Shortens the horizontal part of `displacement` so that the player doesn't walk off the
+    /// edge of whatever it is standing on.
+    fn avoid_edges(&self, mut displacement: Vec3, terrain: &Terrain) -> Vec3 {
+        let feet = self.bounding_box();
+        let feet = BoundingBox::new(
+            vec3(feet.min.x, feet.min.y - EDGE_CHECK_DEPTH, feet.min.z),
+            vec3(feet.max.x, feet.min.y, feet.max.z),
+        );
+        let supported = |x: f32, z: f32| physics::collides(terrain, feet + vec3(x, 0.0, z));
+        let shorten = |d: f32| {
+            if d.abs() < EDGE_CHECK_STEP {
+                0.0
+            } else {
+                d - EDGE_CHECK_STEP.copysign(d)
+            }
+        };
+
+        while displacement.x != 0.0 && !supported(displacement.x, 0.0) {
+            displacement.x = shorten(displacement.x);
+        }
+        while displacement.z != 0.0 && !supported(0.0, displacement.z) {
+            displacement.z = shorten(displacement.z);
+        }
+        while displacement.x != 0.0
+            && displacement.z != 0.0
+            && !supported(displacement.x, displacement.z)
         {
-            self.position += depth * direction;
+            displacement.x = shorten(displacement.x);
+            displacement.z = shorten(displacement.z);
+        }
+
+        displacement
+    }
+
+    /// Called when jump is pressed. Jumps if the player is on the ground, and toggles flight on
+    /// a double tap.
+    pub fn jump(&mut self) {
+        if self.dead {
+            return;
+        }
+
+        let double_tapped = self.time_since_jump.is_some_and(|t| t < DOUBLE_TAP_TIME);
+        self.time_since_jump = Some(0.0);
+
+        match self.movement_mode {
+            MovementMode::Walking if self.can_fly && double_tapped => {
+                self.toggle_movement_mode(MovementMode::Flying);
+                self.time_since_jump = None;
+            }
+            MovementMode::Walking if self.on_ground => self.launch(),
+            MovementMode::Flying if double_tapped => {
+                self.toggle_movement_mode(MovementMode::Flying);
+                self.time_since_jump = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Eats the selected item if it is food and the player is hungry. Returns whether anything
+    /// was eaten.
+    pub fn eat_selected(&mut self) -> bool {
+        if self.hunger.food >= MAX_FOOD {
+            return false;
+        }
+        let Some(food) = self
+            .inventory
+            .selected_stack()
+            .and_then(|stack| stack.item.food())
+        else {
+            return false;
+        };
+        self.inventory.take_selected();
+        self.hunger.eat(food);
+        true
+    }
+
+    /// Leaves the ground at jumping speed.
+    fn launch(&mut self) {
+        self.on_ground = false;
+        self.velocity.y = JUMP_VELOCITY;
+        self.exhaust(if self.is_sprinting() {
+            SPRINT_JUMP_EXHAUSTION
+        } else {
+            JUMP_EXHAUSTION
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{ivec3, vec3, IVec3, Vec3};
+
+    use super::{MovementMode, Player, MAX_FOOD, MAX_HEALTH, MAX_OXYGEN};
+    use crate::item::Item;
+    use crate::terrain::{
+        block::{Block, Facing, FLAT_THICKNESS},
+        Terrain,
+    };
+
+    const DELTA_TIME: f32 = 0.03;
+
+    /// Creates empty (but generated) terrain around the origin containing the given blocks.
+    fn terrain_with(blocks: impl IntoIterator<Item = IVec3>) -> Terrain {
+        terrain_with_water(blocks, [])
+    }
+
+    fn terrain_with_water(
+        blocks: impl IntoIterator<Item = IVec3>,
+        water: impl IntoIterator<Item = IVec3>,
+    ) -> Terrain {
+        let mut terrain = Terrain::empty(-2..=1, -2..=1);
+        for block in blocks {
+            terrain.set_block(block, Block::STONE);
+        }
+        for block in water {
+            terrain.set_block(block, Block::WATER);
+        }
+        terrain
+    }
+
+    fn floor(y: i32) -> impl Iterator<Item = IVec3> {
+        (-8..8).flat_map(move |x| (-8..8).map(move |z| ivec3(x, y, z)))
+    }
+
+    fn player_at(position: Vec3) -> Player {
+        Player {
+            position,
+            oxygen: MAX_OXYGEN,
+            health: MAX_HEALTH,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lands_on_floor() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 12.0, 0.5));
+
+        for _ in 0..100 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert_eq!(player.position.y, 11.0);
+        assert_eq!(player.velocity.y, 0.0);
+        assert!(player.on_ground);
+    }
+
+    #[test]
+    fn fast_fall_does_not_tunnel_through_floor() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 40.0, 0.5));
+        player.velocity.y = -1000.0;
+
+        player.update(DELTA_TIME, &terrain);
+
+        assert_eq!(player.position.y, 11.0);
+        assert!(player.on_ground);
+    }
+
+    #[test]
+    fn long_frame_does_not_tunnel_through_floor() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 200.0, 0.5));
+
+        player.update(10.0, &terrain);
+
+        assert_eq!(player.position.y, 11.0);
+    }
+
+    #[test]
+    fn walking_into_wall_stops_at_its_face() {
+        let wall = (11..14).flat_map(|y| (-8..8).map(move |z| ivec3(3, y, z)));
+        let terrain = terrain_with(floor(10).chain(wall));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.walk_vector = Vec3::X;
+
+        for _ in 0..100 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert_eq!(player.bounding_box().max.x, 3.0);
+        assert_eq!(player.position.z, 0.5);
+    }
+
+    #[test]
+    fn sliding_along_wall_does_not_catch_on_seams() {
+        let wall = (11..14).flat_map(|y| (-8..8).map(move |z| ivec3(3, y, z)));
+        let terrain = terrain_with(floor(10).chain(wall));
+        // Slightly inside the wall, as can happen due to rounding errors.
+        let mut player = player_at(vec3(3.0 - 0.875 + 1e-5, 11.0, -4.0));
+        player.walk_vector = vec3(1.0, 0.0, 1.0).normalize();
+
+        for _ in 0..60 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(
+            player.position.z > 2.0,
+            "stuck at z = {}",
+            player.position.z
+        );
+    }
+
+    #[test]
+    fn walking_across_floor_does_not_catch_on_seams() {
+        let terrain = terrain_with(floor(10));
+        // Slightly inside the floor, as can happen due to rounding errors.
+        let mut player = player_at(vec3(-4.0, 11.0 - 1e-5, 0.5));
+        player.walk_vector = Vec3::X;
+
+        for _ in 0..40 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(
+            player.position.x > 1.0,
+            "stuck at x = {}",
+            player.position.x
+        );
+    }
+
+    #[test]
+    fn walking_into_corner_stops_on_both_axes() {
+        let wall_x = (11..14).flat_map(|y| (-8..8).map(move |z| ivec3(3, y, z)));
+        let wall_z = (11..14).flat_map(|y| (-8..8).map(move |x| ivec3(x, y, 3)));
+        let terrain = terrain_with(floor(10).chain(wall_x).chain(wall_z));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.walk_vector = vec3(1.0, 0.0, 1.0).normalize();
+
+        for _ in 0..100 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        let bounding_box = player.bounding_box();
+        assert_eq!(bounding_box.max.x, 3.0);
+        assert_eq!(bounding_box.max.z, 3.0);
+    }
+
+    #[test]
+    fn can_walk_out_of_block_it_is_stuck_in() {
+        let terrain = terrain_with(floor(10).chain([ivec3(0, 11, 0)]));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.walk_vector = Vec3::X;
+
+        for _ in 0..20 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(player.position.x > 2.0);
+        assert_eq!(player.position.y, 11.0);
+    }
+
+    /// A raised area starting at `x`, `height` blocks above the floor.
+    fn ledge(x: i32, height: i32) -> impl Iterator<Item = IVec3> {
+        (x..8).flat_map(move |x| {
+            (11..11 + height).flat_map(move |y| (-8..8).map(move |z| ivec3(x, y, z)))
+        })
+    }
+
+    #[test]
+    fn steps_up_single_block() {
+        let terrain = terrain_with(floor(10).chain(ledge(3, 1)));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.step_height = 1.0;
+        player.walk_vector = Vec3::X;
+
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(
+            player.position.x > 3.0,
+            "stuck at x = {}",
+            player.position.x
+        );
+        assert_eq!(player.position.y, 12.0);
+        assert!(player.on_ground);
+    }
+
+    #[test]
+    fn does_not_step_without_step_height() {
+        let terrain = terrain_with(floor(10).chain(ledge(3, 1)));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.step_height = 0.0;
+        player.walk_vector = Vec3::X;
+
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert_eq!(player.bounding_box().max.x, 3.0);
+        assert_eq!(player.position.y, 11.0);
+    }
+
+    #[test]
+    fn does_not_step_up_two_blocks() {
+        let terrain = terrain_with(floor(10).chain(ledge(3, 2)));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.step_height = 1.0;
+        player.walk_vector = Vec3::X;
+
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert_eq!(player.bounding_box().max.x, 3.0);
+        assert_eq!(player.position.y, 11.0);
+    }
+
+    #[test]
+    fn does_not_step_up_into_low_ceiling() {
+        let ceiling =
+            (14..15).flat_map(|y| (3..8).flat_map(move |x| (-8..8).map(move |z| ivec3(x, y, z))));
+        let terrain = terrain_with(floor(10).chain(ledge(3, 1)).chain(ceiling));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.step_height = 1.0;
+        player.walk_vector = Vec3::X;
+
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
 
-            let normal_velocity = self.velocity.dot(direction);
-            if normal_velocity < 0.0 {
-                self.velocity -= normal_velocity * direction;
+        assert_eq!(player.bounding_box().max.x, 3.0);
+        assert_eq!(player.position.y, 11.0);
+    }
+
+    #[test]
+    fn auto_jumps_onto_two_block_ledge() {
+        let terrain = terrain_with(floor(10).chain(ledge(3, 2)));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.step_height = 1.0;
+        player.auto_jump = true;
+        player.walk_vector = Vec3::X;
+
+        for _ in 0..60 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(
+            player.position.x > 3.0,
+            "stuck at x = {}",
+            player.position.x
+        );
+        assert_eq!(player.position.y, 13.0);
+    }
+
+    #[test]
+    fn jumping_into_ceiling_stops_upward_motion() {
+        let terrain = terrain_with(floor(10).chain(floor(14)));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.update(DELTA_TIME, &terrain);
+
+        player.jump();
+        player.update(DELTA_TIME, &terrain);
+        player.update(DELTA_TIME, &terrain);
+
+        // The player bumps its head and is already falling again.
+        let top = player.bounding_box().max.y;
+        assert!(top > 13.9 && top <= 14.0, "top = {top}");
+        assert!(player.velocity.y <= 0.0);
+    }
+
+    #[test]
+    fn flying_ignores_gravity() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 20.0, 0.5));
+        player.movement_mode = MovementMode::Flying;
+
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert_eq!(player.position.y, 20.0);
+    }
+
+    #[test]
+    fn double_tapping_jump_toggles_flight() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.can_fly = true;
+        player.update(DELTA_TIME, &terrain);
+
+        player.jump();
+        player.update(DELTA_TIME, &terrain);
+        player.jump();
+        assert_eq!(player.movement_mode, MovementMode::Flying);
+
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
+        player.jump();
+        player.update(DELTA_TIME, &terrain);
+        player.jump();
+        assert_eq!(player.movement_mode, MovementMode::Walking);
+    }
+
+    #[test]
+    fn slow_jumps_do_not_toggle_flight() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.can_fly = true;
+
+        for _ in 0..3 {
+            player.jump();
+            for _ in 0..100 {
+                player.update(DELTA_TIME, &terrain);
             }
+        }
+
+        assert_eq!(player.movement_mode, MovementMode::Walking);
+    }
+
+    #[test]
+    fn flying_into_ground_lands() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 13.0, 0.5));
+        player.movement_mode = MovementMode::Flying;
+        player.walk_vector = -Vec3::Y;
+
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert_eq!(player.position.y, 11.0);
+        assert_eq!(player.movement_mode, MovementMode::Walking);
+    }
+
+    #[test]
+    fn noclip_passes_through_walls() {
+        let terrain = terrain_with(floor(10).chain(ledge(3, 3)));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.movement_mode = MovementMode::Noclip;
+        player.walk_vector = Vec3::X;
+
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(player.position.x > 5.0);
+        assert_eq!(player.position.y, 11.0);
+    }
+
+    #[test]
+    fn spectators_cannot_interact() {
+        let mut player = player_at(Vec3::ZERO);
+        assert!(player.can_interact());
+
+        player.toggle_movement_mode(MovementMode::Spectator);
+        assert!(!player.can_interact());
+
+        player.toggle_movement_mode(MovementMode::Spectator);
+        assert_eq!(player.movement_mode, MovementMode::Walking);
+    }
+
+    /// Water filling the space above the floor up to (but not including) `top`.
+    fn pool(top: i32) -> impl Iterator<Item = IVec3> {
+        (11..top).flat_map(floor)
+    }
+
+    #[test]
+    fn sinks_slowly_in_water() {
+        let terrain = terrain_with_water(floor(10), pool(20));
+        let mut player = player_at(vec3(0.5, 15.0, 0.5));
+
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(player.position.y < 15.0);
+        assert!(player.position.y > 13.0, "sank to {}", player.position.y);
+    }
+
+    #[test]
+    fn water_slows_falls() {
+        let terrain = terrain_with_water(floor(10), pool(20));
+        let mut player = player_at(vec3(0.5, 19.0, 0.5));
+        player.velocity.y = -30.0;
+
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(player.velocity.y > -5.0, "velocity = {}", player.velocity.y);
+    }
+
+    #[test]
+    fn swims_up_while_holding_jump() {
+        let terrain = terrain_with_water(floor(10), pool(20));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.walk_vector.y = 1.0;
+
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(
+            player.position.y > 13.0,
+            "only reached {}",
+            player.position.y
+        );
+    }
+
+    #[test]
+    fn walks_slower_in_water() {
+        let terrain = terrain_with_water(floor(10), pool(12));
+        let mut player = player_at(vec3(-4.0, 11.0, 0.5));
+        player.walk_vector = Vec3::X;
+
+        for _ in 0..10 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(player.position.x - -4.0 < 0.8);
+    }
+
+    #[test]
+    fn runs_out_of_oxygen_under_water() {
+        let terrain = terrain_with_water(floor(10), pool(20));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.oxygen = 1.0;
+
+        for _ in 0..10 {
+            player.update(DELTA_TIME, &terrain);
+        }
+        assert!(player.oxygen < 1.0);
+
+        for _ in 0..100 {
+            player.update(DELTA_TIME, &terrain);
+        }
+        assert_eq!(player.oxygen, 0.0);
+    }
+
+    #[test]
+    fn regains_oxygen_out_of_water() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.oxygen = 0.0;
+
+        for _ in 0..200 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert_eq!(player.oxygen_fraction(), 1.0);
+    }
+
+    #[test]
+    fn climbs_ladders() {
+        let wall = (11..30).flat_map(|y| (-8..8).map(move |z| ivec3(3, y, z)));
+        let mut terrain = terrain_with(floor(10).chain(wall));
+        for y in 11..30 {
+            terrain.set_block(ivec3(2, y, 0), Block::LADDER.with_facing(Facing::West));
+        }
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+
+        // Ladders are thin, so the player walks into them before reaching the wall, and keeps
+        // going up them.
+        player.walk_vector = Vec3::X;
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
+        assert_eq!(player.bounding_box().max.x, 3.0 - FLAT_THICKNESS);
+        assert!(player.is_climbing(&terrain));
+        assert!(player.position.y > 12.0);
+
+        // Walking along the wall doesn't climb, but jumping does.
+        let height = player.position.y;
+        player.walk_vector = Vec3::Z;
+        player.update(DELTA_TIME, &terrain);
+        assert!(player.position.y < height);
+
+        player.walk_vector = Vec3::Y;
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
+        let height = player.position.y;
+        assert!(height > 13.0);
+
+        player.sneaking = true;
+        player.walk_vector = vec3(1.0, -1.0, 0.0);
+        for _ in 0..30 {
+            player.update(DELTA_TIME, &terrain);
+        }
+        assert_eq!(player.position.y, height);
+
+        player.sneaking = false;
+        player.walk_vector = Vec3::ZERO;
+        for _ in 0..100 {
+            player.update(DELTA_TIME, &terrain);
+        }
+        assert_eq!(player.position.y, 11.0);
+        assert_eq!(player.health, MAX_HEALTH);
+    }
+
+    #[test]
+    fn sneaking_lowers_head_and_bounding_box() {
+        let mut player = player_at(Vec3::ZERO);
+        let standing_head = player.head_position();
+        let standing_box = player.bounding_box();
+
+        player.sneaking = true;
+
+        assert!(player.head_position().y < standing_head.y);
+        assert!(player.bounding_box().max.y < standing_box.max.y);
+        assert!(player.head_position().y < player.bounding_box().max.y);
+    }
+
+    #[test]
+    fn sneaking_is_slower() {
+        let terrain = terrain_with(floor(10));
+        let mut walking = player_at(vec3(-4.0, 11.0, 0.5));
+        walking.walk_vector = Vec3::X;
+        let mut sneaking = player_at(vec3(-4.0, 11.0, 0.5));
+        sneaking.walk_vector = Vec3::X;
+        sneaking.sneaking = true;
+
+        for _ in 0..10 {
+            walking.update(DELTA_TIME, &terrain);
+            sneaking.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(sneaking.position.x < walking.position.x);
+    }
+
+    #[test]
+    fn sneaking_does_not_walk_off_edges() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(4.0, 11.0, 0.5));
+        player.walk_vector = vec3(1.0, 0.0, 0.3).normalize();
+        player.sneaking = true;
+
+        for _ in 0..200 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert_eq!(player.position.y, 11.0);
+        assert!(player.bounding_box().min.x < 8.0);
+        assert!(player.bounding_box().min.x > 7.8);
+    }
+
+    #[test]
+    fn sneaking_does_not_walk_off_corners() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(4.0, 11.0, 4.0));
+        player.walk_vector = vec3(1.0, 0.0, 1.0).normalize();
+        player.sneaking = true;
+
+        for _ in 0..200 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert_eq!(player.position.y, 11.0);
+    }
+
+    #[test]
+    fn walking_without_sneaking_falls_off_edges() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(4.0, 11.0, 0.5));
+        player.walk_vector = Vec3::X;
+
+        for _ in 0..200 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(player.position.y < 11.0);
+    }
+
+    #[test]
+    fn sneaking_has_no_effect_while_flying() {
+        let mut player = player_at(Vec3::ZERO);
+        player.movement_mode = MovementMode::Flying;
+        let head = player.head_position();
 
-            if direction == Vec3::Y {
-                self.on_ground = true;
+        player.sneaking = true;
+
+        assert_eq!(player.head_position(), head);
+    }
+
+    #[test]
+    fn long_falls_hurt() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 30.0, 0.5));
+
+        for _ in 0..100 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(player.on_ground);
+        assert!(player.health < MAX_HEALTH);
+        assert!(!player.is_dead());
+    }
+
+    #[test]
+    fn jumping_does_not_hurt() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+
+        for _ in 0..5 {
+            player.jump();
+            for _ in 0..50 {
+                player.update(DELTA_TIME, &terrain);
             }
         }
+
+        assert_eq!(player.health, MAX_HEALTH);
     }
 
-    pub fn jump(&mut self) {
-        if self.on_ground {
-            self.on_ground = false;
-            self.velocity.y = JUMP_VELOCITY;
+    #[test]
+    fn very_long_falls_kill() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 100.0, 0.5));
+
+        for _ in 0..200 {
+            player.update(DELTA_TIME, &terrain);
         }
+
+        assert!(player.is_dead());
+        assert_eq!(player.health, 0.0);
+    }
+
+    #[test]
+    fn suffocates_inside_blocks() {
+        let terrain = terrain_with((10..14).flat_map(floor));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+
+        player.update(DELTA_TIME, &terrain);
+
+        assert!(player.health < MAX_HEALTH);
+    }
+
+    #[test]
+    fn drowns_without_oxygen() {
+        let terrain = terrain_with_water(floor(10), pool(20));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.oxygen = 0.0;
+
+        player.update(DELTA_TIME, &terrain);
+
+        assert!(player.health < MAX_HEALTH);
+    }
+
+    #[test]
+    fn dead_players_do_not_move() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 20.0, 0.5));
+        player.damage(MAX_HEALTH);
+
+        player.update(DELTA_TIME, &terrain);
+        player.jump();
+
+        assert_eq!(player.position, vec3(0.5, 20.0, 0.5));
+        assert_eq!(player.velocity, Vec3::ZERO);
+    }
+
+    #[test]
+    fn respawns_at_spawn_point() {
+        let mut player = player_at(vec3(0.5, 20.0, 0.5));
+        player.spawn_point = vec3(3.5, 11.0, 3.5);
+        player.damage(MAX_HEALTH);
+
+        player.respawn();
+
+        assert!(!player.is_dead());
+        assert_eq!(player.health, MAX_HEALTH);
+        assert_eq!(player.position, vec3(3.5, 11.0, 3.5));
+    }
+
+    #[test]
+    fn sprinting_uses_up_food() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(-7.5, 11.0, 0.5));
+        player.hunger.saturation = 0.0;
+        player.walk_vector = Vec3::X;
+        player.sprinting = true;
+
+        for _ in 0..100 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(player.position.x > 7.0);
+        assert!(player.hunger.exhaustion > 1.0);
+    }
+
+    #[test]
+    fn cannot_sprint_when_hungry() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(-7.5, 11.0, 0.5));
+        player.hunger.food = 2;
+        player.walk_vector = Vec3::X;
+        player.sprinting = true;
+
+        for _ in 0..10 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(!player.is_sprinting());
+        assert!((player.position.x - -6.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn regenerates_when_well_fed() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.health = 10.0;
+
+        for _ in 0..100 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert!(player.health > 10.0);
+        assert!(player.hunger.saturation < 5.0);
+    }
+
+    #[test]
+    fn does_not_regenerate_when_hungry() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.health = 10.0;
+        player.hunger.food = 10;
+
+        for _ in 0..100 {
+            player.update(DELTA_TIME, &terrain);
+        }
+
+        assert_eq!(player.health, 10.0);
+    }
+
+    #[test]
+    fn jumping_and_damage_use_up_food() {
+        let terrain = terrain_with(floor(10));
+        let mut player = player_at(vec3(0.5, 11.0, 0.5));
+        player.update(DELTA_TIME, &terrain);
+
+        player.jump();
+        let after_jump = player.hunger.exhaustion;
+        player.damage(5.0);
+
+        assert!(after_jump > 0.0);
+        assert!(player.hunger.exhaustion > after_jump);
+        assert_eq!(player.hunger.food, MAX_FOOD);
+    }
+
+    #[test]
+    fn eating_food_from_the_hotbar() {
+        let mut player = player_at(Vec3::ZERO);
+        player.hunger.food = 10;
+        player.inventory.add(Item::Bread, 2);
+
+        assert!(player.eat_selected());
+
+        assert_eq!(player.hunger.food, 15);
+        assert_eq!(player.inventory.selected_stack().unwrap().count, 1);
+    }
+
+    #[test]
+    fn cannot_eat_blocks() {
+        let mut player = player_at(Vec3::ZERO);
+        player.inventory.add(Item::Block(Block::DIRT), 1);
+
+        assert!(!player.eat_selected());
+        assert_eq!(player.inventory.selected_stack().unwrap().count, 1);
+    }
+
+    #[test]
+    fn waits_for_terrain_to_load() {
+        let mut terrain = terrain_with(floor(10));
+        let chunk = terrain.chunks.remove(&(0, 0)).unwrap();
+        let mut player = player_at(vec3(0.5, 12.0, 0.5));
+        player.walk_vector = vec3(0.0, 0.0, 1.0);
+
+        for _ in 0..100 {
+            player.update(DELTA_TIME, &terrain);
+        }
+        assert_eq!(player.position, vec3(0.5, 12.0, 0.5));
+
+        terrain.chunks.insert((0, 0), chunk);
+        player.update(DELTA_TIME, &terrain);
+        assert!(player.position.z > 0.5);
     }
 }
@@ -0,0 +1,83 @@
+use std::fmt;
+
+use crate::{game_mode::GameMode, parse_error::ParseError};
+
+/// A world's settings, which are kept between sessions as `key = value` lines. This is not a
+/// full save: the terrain is generated again from the seed, so changes to the blocks, the
+/// player and everything else in the world are lost.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SaveData {
+    /// The seed the terrain is generated from.
+    pub seed: u64,
+    pub game_mode: GameMode,
+}
+
+impl SaveData {
+    /// Reads a settings file. Anything the file doesn't mention keeps its default.
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let mut data = Self::default();
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(ParseError::new(line_number, "expected `key = value`"));
+            };
+            let value = value.trim();
+            match key.trim() {
+                "seed" => {
+                    data.seed = value.parse().map_err(|_| {
+                        ParseError::new(line_number, format!("invalid seed `{value}`"))
+                    })?;
+                }
+                "game_mode" => {
+                    data.game_mode = GameMode::from_name(value).ok_or_else(|| {
+                        ParseError::new(line_number, format!("unknown game mode `{value}`"))
+                    })?;
+                }
+                key => return Err(ParseError::new(line_number, format!("unknown key `{key}`"))),
+            }
+        }
+        Ok(data)
+    }
+}
+
+impl fmt::Display for SaveData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "seed = {}", self.seed)?;
+        writeln!(f, "game_mode = {}", self.game_mode.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SaveData;
+    use crate::{game_mode::GameMode, parse_error::ParseError};
+
+    #[test]
+    fn round_trips() {
+        let data = SaveData {
+            seed: 42,
+            game_mode: GameMode::Creative,
+        };
+
+        assert_eq!(SaveData::parse(&data.to_string()), Ok(data));
+        assert_eq!(SaveData::parse("# Nothing yet\n"), Ok(SaveData::default()));
+        assert_eq!(
+            SaveData::parse("game_mode = adventure"),
+            Err(ParseError {
+                line: 1,
+                message: "unknown game mode `adventure`".to_string()
+            })
+        );
+        assert_eq!(
+            SaveData::parse("seed = -1"),
+            Err(ParseError {
+                line: 1,
+                message: "invalid seed `-1`".to_string()
+            })
+        );
+    }
+}
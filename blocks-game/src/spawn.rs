@@ -0,0 +1,105 @@
+use glam::{ivec3, vec3, IVec3, Vec3};
+
+use crate::{
+    bounding_box::BoundingBox,
+    physics,
+    terrain::{block::Block, subchunk::Subchunk, Terrain},
+};
+
+/// How far from the origin to look for somewhere to spawn.
+pub const SEARCH_RADIUS: i32 = 16;
+
+/// Finds where to spawn: on dry ground in the column nearest the origin with room for something
+/// with `bounds` (relative to its feet) to stand. Falls back to on top of whatever is at the
+/// origin if there's nowhere dry. Returns `None` until every column within [`SEARCH_RADIUS`] has
+/// been generated.
+pub fn find_spawn_point(terrain: &Terrain, bounds: BoundingBox) -> Option<Vec3> {
+    let min_chunk = (-SEARCH_RADIUS).div_euclid(Subchunk::SIZE as i32);
+    let max_chunk = SEARCH_RADIUS.div_euclid(Subchunk::SIZE as i32);
+    let generated = (min_chunk..=max_chunk)
+        .all(|x| (min_chunk..=max_chunk).all(|z| terrain.chunks.contains_key(&(x, z))));
+    if !generated {
+        return None;
+    }
+
+    let mut columns: Vec<_> = (-SEARCH_RADIUS..=SEARCH_RADIUS)
+        .flat_map(|x| (-SEARCH_RADIUS..=SEARCH_RADIUS).map(move |z| ivec3(x, 0, z)))
+        .filter(|column| column.length_squared() <= SEARCH_RADIUS * SEARCH_RADIUS)
+        .collect();
+    columns.sort_by_key(|column| column.length_squared());
+
+    let dry_ground = columns.into_iter().find_map(|column| {
+        let y = terrain.surface_height(column.x, column.z)?;
+        let feet = standing_on(ivec3(column.x, y, column.z));
+        has_room(terrain, bounds + feet).then_some(feet)
+    });
+    dry_ground.or_else(|| {
+        let top = (0..256)
+            .rev()
+            .find(|&y| terrain.block(ivec3(0, y, 0)) != Block::AIR)
+            .unwrap_or(0);
+        Some(standing_on(ivec3(0, top, 0)))
+    })
+}
+
+/// Where to stand on top of the block at `block_pos`.
+fn standing_on(block_pos: IVec3) -> Vec3 {
+    block_pos.as_vec3() + vec3(0.5, 1.0, 0.5)
+}
+
+/// Whether `bounds` is clear of anything solid or liquid.
+fn has_room(terrain: &Terrain, bounds: BoundingBox) -> bool {
+    // Shrunk slightly so that the ground underfoot and the sides of neighbouring blocks don't
+    // count.
+    let bounds = bounds.expand_by(-BoundingBox::EPSILON);
+    !physics::collides(terrain, bounds) && !physics::in_liquid(terrain, bounds)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{ivec3, vec3};
+
+    use super::find_spawn_point;
+    use crate::{
+        bounding_box::BoundingBox,
+        terrain::{block::Block, Terrain},
+    };
+
+    fn bounds() -> BoundingBox {
+        BoundingBox::new(vec3(-0.875, 0.0, -0.875), vec3(0.875, 2.75, 0.875))
+    }
+
+    fn flat_terrain(height: i32) -> Terrain {
+        Terrain::with_floor(-1..=1, -1..=1, height, Block::STONE)
+    }
+
+    #[test]
+    fn waits_for_terrain_around_the_origin() {
+        let mut terrain = flat_terrain(40);
+        terrain.chunks.remove(&(-1, 0));
+
+        assert_eq!(find_spawn_point(&terrain, bounds()), None);
+    }
+
+    #[test]
+    fn needs_dry_ground_with_room_to_stand() {
+        let mut terrain = flat_terrain(40);
+        // Flood everything near the origin, leaving dry ground from x = 4.
+        for x in -16..4 {
+            for z in -16..32 {
+                terrain.set_block(ivec3(x, 41, z), Block::WATER);
+            }
+        }
+        // And put a wall along x = 6 that's too close to stand next to.
+        for y in 41..44 {
+            for z in -2..=2 {
+                terrain.set_block(ivec3(6, y, z), Block::STONE);
+            }
+        }
+
+        assert_eq!(
+            find_spawn_point(&terrain, bounds()),
+            Some(vec3(6.5, 44.0, 0.5))
+        );
+    }
+}
@@ -1,3 +1,43 @@
+use glam::{ivec3, vec3, IVec3, Vec3};
+
+use crate::bounding_box::BoundingBox;
+
+/// How thick blocks lying flat against a wall or the ground, such as ladders and snow, are.
+pub const FLAT_THICKNESS: f32 = 0.125;
+
+/// Which way a block attached to a wall faces, away from the wall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Facing {
+    /// Towards -z.
+    North,
+    /// Towards +z.
+    South,
+    /// Towards -x.
+    West,
+    /// Towards +x.
+    East,
+}
+
+impl Facing {
+    pub const ALL: [Self; 4] = [Self::North, Self::South, Self::West, Self::East];
+
+    /// The facing pointing along `direction`, if it's horizontal.
+    pub fn from_direction(direction: IVec3) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|facing| facing.direction() == direction)
+    }
+
+    pub fn direction(self) -> IVec3 {
+        match self {
+            Self::North => ivec3(0, 0, -1),
+            Self::South => ivec3(0, 0, 1),
+            Self::West => ivec3(-1, 0, 0),
+            Self::East => ivec3(1, 0, 0),
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
 #[repr(C)]
 pub struct Block(u8);
@@ -7,4 +47,154 @@ impl Block {
     pub const STONE: Self = Self(1);
     pub const GRASS: Self = Self(2);
     pub const DIRT: Self = Self(3);
+    pub const WATER: Self = Self(4);
+    /// A thin layer of snow lying on top of another block.
+    pub const SNOW: Self = Self(5);
+    /// Vines hanging on a wall and facing north, which can be climbed. Vines facing other ways
+    /// follow them, in the order of [`Facing::ALL`].
+    pub const VINES: Self = Self(6);
+    /// A ladder facing north, with ladders facing other ways following it like vines.
+    pub const LADDER: Self = Self(10);
+
+    pub const ALL: [Self; 8] = [
+        Self::AIR,
+        Self::STONE,
+        Self::GRASS,
+        Self::DIRT,
+        Self::WATER,
+        Self::SNOW,
+        Self::VINES,
+        Self::LADDER,
+    ];
+
+    /// Blocks that are attached to walls, each as the variant facing north.
+    const ORIENTED: [Self; 2] = [Self::VINES, Self::LADDER];
+
+    /// The unoriented block and its facing, if it's attached to a wall.
+    fn orientation(self) -> Option<(Self, Facing)> {
+        Self::ORIENTED.into_iter().find_map(|base| {
+            let index = self.0.checked_sub(base.0)?;
+            Some((base, *Facing::ALL.get(index as usize)?))
+        })
+    }
+
+    /// This block attached to the wall behind it and facing `facing`. Blocks that don't attach
+    /// to walls are left as they are.
+    pub fn with_facing(self, facing: Facing) -> Self {
+        match self.orientation() {
+            Some((base, _)) => Self(base.0 + facing as u8),
+            None => self,
+        }
+    }
+
+    /// Which way the block faces, if it's attached to a wall.
+    pub fn facing(self) -> Option<Facing> {
+        self.orientation().map(|(_, facing)| facing)
+    }
+
+    /// The block without any facing, which is what it is as an item.
+    pub fn unoriented(self) -> Self {
+        self.orientation().map_or(self, |(base, _)| base)
+    }
+
+    /// The block as placed against the side `face` of another block, or `None` if it can't be
+    /// placed there.
+    pub fn placed_against(self, face: IVec3) -> Option<Self> {
+        if self.facing().is_some() {
+            Facing::from_direction(face).map(|facing| self.with_facing(facing))
+        } else {
+            Some(self)
+        }
+    }
+
+    /// The name used for the block in data files.
+    pub fn name(self) -> &'static str {
+        match self.unoriented() {
+            Self::AIR => "air",
+            Self::STONE => "stone",
+            Self::GRASS => "grass",
+            Self::DIRT => "dirt",
+            Self::WATER => "water",
+            Self::SNOW => "snow",
+            Self::VINES => "vines",
+            Self::LADDER => "ladder",
+            _ => "unknown",
+        }
+    }
+
+    /// A flat colour standing in for the block, such as in item icons and maps.
+    pub fn color(self) -> [u8; 3] {
+        match self.unoriented() {
+            Self::STONE => [125, 125, 125],
+            Self::GRASS => [95, 159, 53],
+            Self::DIRT => [134, 96, 67],
+            Self::WATER => [38, 77, 204],
+            Self::SNOW => [242, 247, 255],
+            Self::VINES => [51, 128, 38],
+            Self::LADDER => [153, 115, 64],
+            _ => [255, 0, 255],
+        }
+    }
+
+    /// Whether this block fills its whole space, so that entities collide with it and it hides
+    /// whatever is behind it.
+    pub fn is_solid(self) -> bool {
+        matches!(self, Self::STONE | Self::GRASS | Self::DIRT)
+    }
+
+    /// Whether the player can aim at this block, to break it or place things against it.
+    pub fn is_targetable(self) -> bool {
+        self.is_solid() || self.is_climbable() || self == Self::SNOW
+    }
+
+    /// Whether a block placed against this one takes its place rather than going next to it.
+    pub fn is_replaceable(self) -> bool {
+        self == Self::SNOW
+    }
+
+    /// Whether standing in this block lets the player climb up and down.
+    pub fn is_climbable(self) -> bool {
+        self.facing().is_some()
+    }
+
+    /// The space this block at `block_pos` takes up, which is what the player aims at to break
+    /// it.
+    pub fn shape(self, block_pos: IVec3) -> BoundingBox {
+        if self == Self::SNOW {
+            let layer = BoundingBox::new(Vec3::ZERO, vec3(1.0, FLAT_THICKNESS, 1.0));
+            return layer + block_pos.as_vec3();
+        }
+        let Some(facing) = self.facing() else {
+            return BoundingBox::of_block(block_pos);
+        };
+        // Flat against the side of the block that it faces away from.
+        let direction = facing.direction().as_vec3();
+        let min = Vec3::ZERO.max(-direction * (1.0 - FLAT_THICKNESS));
+        let max = Vec3::ONE.min(Vec3::ONE - direction * (1.0 - FLAT_THICKNESS));
+        BoundingBox::new(min, max) + block_pos.as_vec3()
+    }
+
+    /// The box that entities collide with for this block at `block_pos`, if any. Vines can be
+    /// walked through, but ladders can't.
+    pub fn collision_box(self, block_pos: IVec3) -> Option<BoundingBox> {
+        (self.is_solid() || self.unoriented() == Self::LADDER).then(|| self.shape(block_pos))
+    }
+
+    pub fn is_liquid(self) -> bool {
+        self == Self::WATER
+    }
+
+    /// How long the block takes to break by hand, roughly in seconds. `None` if it can't be
+    /// broken at all.
+    pub fn hardness(self) -> Option<f32> {
+        match self.unoriented() {
+            Self::STONE => Some(1.5),
+            Self::GRASS => Some(0.6),
+            Self::DIRT => Some(0.5),
+            Self::SNOW => Some(0.1),
+            Self::VINES => Some(0.2),
+            Self::LADDER => Some(0.4),
+            _ => None,
+        }
+    }
 }
@@ -47,7 +47,13 @@ impl Chunk {
         let max_subchunk_y = (bounding_box.max.y.floor() as i32).div_euclid(Subchunk::SIZE as i32);
 
         (min_subchunk_y..=max_subchunk_y)
-            .filter(|y| (0..self.subchunks.len() as i32).contains(&y))
+            .filter(|y| (0..self.subchunks.len() as i32).contains(y))
             .map(|y| (y, &self.subchunks[y as usize]))
     }
 }
+
+impl Default for Chunk {
+    fn default() -> Self {
+        Self::new()
+    }
+}
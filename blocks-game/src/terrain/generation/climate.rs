@@ -0,0 +1,79 @@
+use glam::IVec3;
+
+use super::{random::Random, seed_for_chunk, SEA_LEVEL};
+
+/// Mixed into the world seed so that the climate doesn't follow the shape of the terrain.
+const CLIMATE_SALT: u64 = 0xC11_4A7E;
+/// Width in blocks of the areas that share a climate.
+const CLIMATE_SCALE: f32 = 256.0;
+/// How much colder it gets per block above sea level.
+const ALTITUDE_COOLING: f32 = 0.01;
+/// Below this temperature precipitation falls as snow.
+const FREEZING: f32 = 0.3;
+/// Above this temperature it is too dry for anything to fall.
+const ARID: f32 = 0.9;
+
+/// What falls from the sky when the weather isn't clear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precipitation {
+    None,
+    Rain,
+    Snow,
+}
+
+/// How warm it is at `position`, from 0 in the coldest places to 1 in the hottest ones at sea
+/// level. Gets colder higher up.
+pub fn temperature(seed: u64, position: IVec3) -> f32 {
+    let x = position.x as f32 / CLIMATE_SCALE;
+    let z = position.z as f32 / CLIMATE_SCALE;
+    let (cell_x, cell_z) = (x.floor() as i32, z.floor() as i32);
+    let corner = |dx: i32, dz: i32| {
+        let seed = seed_for_chunk(seed ^ CLIMATE_SALT, cell_x + dx, cell_z + dz);
+        Random::with_seed(seed).next_float()
+    };
+
+    let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+    let u = smooth(x - cell_x as f32);
+    let v = smooth(z - cell_z as f32);
+    let north = corner(0, 0) + u * (corner(1, 0) - corner(0, 0));
+    let south = corner(0, 1) + u * (corner(1, 1) - corner(0, 1));
+    let base = north + v * (south - north);
+
+    base - (position.y - SEA_LEVEL).max(0) as f32 * ALTITUDE_COOLING
+}
+
+pub fn precipitation(seed: u64, position: IVec3) -> Precipitation {
+    let temperature = temperature(seed, position);
+    if temperature < FREEZING {
+        Precipitation::Snow
+    } else if temperature > ARID {
+        Precipitation::None
+    } else {
+        Precipitation::Rain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::ivec3;
+
+    use super::{precipitation, temperature, Precipitation, SEA_LEVEL};
+
+    #[test]
+    fn mountain_tops_are_colder() {
+        let low = temperature(7, ivec3(100, SEA_LEVEL, 100));
+        let high = temperature(7, ivec3(100, SEA_LEVEL + 40, 100));
+        assert!(high < low);
+        assert_eq!(
+            precipitation(7, ivec3(100, SEA_LEVEL + 200, 100)),
+            Precipitation::Snow
+        );
+    }
+
+    #[test]
+    fn climate_changes_gradually() {
+        let a = temperature(7, ivec3(1000, SEA_LEVEL, 0));
+        let b = temperature(7, ivec3(1001, SEA_LEVEL, 0));
+        assert!((a - b).abs() < 0.05);
+    }
+}
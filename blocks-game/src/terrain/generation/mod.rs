@@ -1,21 +1,36 @@
-use glam::{ivec2, ivec3};
+use glam::{ivec2, ivec3, IVec3};
 
-use super::{block::Block, chunk::Chunk, subchunk::Subchunk};
+use super::{
+    block::{Block, Facing},
+    chunk::Chunk,
+    subchunk::Subchunk,
+};
 
-use self::noise::PerlinNoise;
+use self::{noise::PerlinNoise, random::Random};
 
+pub mod climate;
 mod noise;
-mod random;
+pub mod random;
 
-pub fn generate_chunk(x: i32, z: i32) -> Chunk {
-    let mut chunk = base_land_mass(x, z);
+/// Empty space below this height is filled with water.
+pub const SEA_LEVEL: i32 = 60;
+/// Mixed into the seed for each chunk so that vines don't follow the shape of the terrain.
+const VINES_SALT: u64 = 0x7153;
+/// Chance that an open spot on a wall above sea level has vines hanging from it.
+const VINES_CHANCE: f32 = 0.01;
+/// How far vines hang down at most.
+const MAX_VINES_LENGTH: i32 = 6;
+
+pub fn generate_chunk(seed: u64, x: i32, z: i32) -> Chunk {
+    let mut chunk = base_land_mass(seed, x, z);
     add_grass(&mut chunk);
+    add_vines(&mut chunk, seed, x, z);
     chunk
 }
 
-fn base_land_mass(x: i32, z: i32) -> Chunk {
+fn base_land_mass(seed: u64, x: i32, z: i32) -> Chunk {
     let mut chunk = Chunk::new();
-    let noise = PerlinNoise::new(ivec2(x, z), ivec3(1, 16, 1));
+    let noise = PerlinNoise::new(seed, ivec2(x, z), ivec3(1, 16, 1));
 
     for i in 0..16 {
         let mut subchunk = Subchunk::new();
@@ -26,6 +41,8 @@ fn base_land_mass(x: i32, z: i32) -> Chunk {
                     let p = ivec3(x, y, z).as_vec3() / 16.0;
                     let block = if noise.sample(p) > (y as f32 - 64.0) / 16.0 {
                         Block::STONE
+                    } else if y < SEA_LEVEL {
+                        Block::WATER
                     } else {
                         Block::AIR
                     };
@@ -43,7 +60,7 @@ fn add_grass(chunk: &mut Chunk) {
     for x in 0..16 {
         for z in 0..16 {
             let mut y = 255;
-            while y > 0 && chunk.block(x, y, z) == Block::AIR {
+            while y > 0 && !chunk.block(x, y, z).is_solid() {
                 y -= 1;
             }
             if y > 0 && chunk.block(x, y, z) == Block::STONE {
@@ -60,6 +77,108 @@ fn add_grass(chunk: &mut Chunk) {
     }
 }
 
-fn seed_for_chunk(x: i32, z: i32) -> u64 {
-    (x as u64) ^ (z as u64) << 16
+/// Hangs vines down walls above sea level. Only walls within the chunk get vines, so that
+/// chunks don't depend on their neighbours.
+fn add_vines(chunk: &mut Chunk, seed: u64, chunk_x: i32, chunk_z: i32) {
+    let mut random = Random::with_seed(seed_for_chunk(seed ^ VINES_SALT, chunk_x, chunk_z));
+    let size = Subchunk::SIZE as i32;
+    let height = chunk.subchunks.len() as i32 * size;
+    let block = |chunk: &Chunk, p: IVec3| chunk.block(p.x as usize, p.y as usize, p.z as usize);
+
+    for x in 0..size {
+        for z in 0..size {
+            for y in SEA_LEVEL..height {
+                let top = ivec3(x, y, z);
+                if block(chunk, top) != Block::AIR {
+                    continue;
+                }
+                for facing in Facing::ALL {
+                    let on_wall = |chunk: &Chunk, p: IVec3| {
+                        let wall = p - facing.direction();
+                        wall.x >= 0
+                            && wall.x < size
+                            && wall.z >= 0
+                            && wall.z < size
+                            && block(chunk, wall).is_solid()
+                    };
+                    if !on_wall(chunk, top) || random.next_float() >= VINES_CHANCE {
+                        continue;
+                    }
+                    let length = 1 + random.next_int_bounded(MAX_VINES_LENGTH);
+                    let vines = Block::VINES.with_facing(facing);
+                    for p in (0..length).map(|i| top - i * IVec3::Y) {
+                        if p.y < SEA_LEVEL || block(chunk, p) != Block::AIR || !on_wall(chunk, p) {
+                            break;
+                        }
+                        chunk.set_block(p.x as usize, p.y as usize, p.z as usize, vines);
+                    }
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn seed_for_chunk(seed: u64, x: i32, z: i32) -> u64 {
+    // Spread the world seed across all of the bits so that nearby seeds give unrelated worlds.
+    seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (x as u64) ^ (z as u64) << 16
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::ivec3;
+
+    use super::{generate_chunk, SEA_LEVEL};
+    use crate::terrain::block::Block;
+
+    #[test]
+    fn fills_empty_space_below_sea_level_with_water() {
+        let mut water = 0;
+        for chunk_x in -4..4 {
+            for chunk_z in -4..4 {
+                let chunk = generate_chunk(0, chunk_x, chunk_z);
+                for x in 0..16 {
+                    for z in 0..16 {
+                        for y in 0..256 {
+                            let block = chunk.block(x, y, z);
+                            if (y as i32) < SEA_LEVEL {
+                                assert_ne!(block, Block::AIR);
+                            } else {
+                                assert_ne!(block, Block::WATER);
+                            }
+                            water += (block == Block::WATER) as u32;
+                        }
+                    }
+                }
+            }
+        }
+        assert!(water > 0);
+    }
+
+    #[test]
+    fn hangs_vines_on_walls_above_sea_level() {
+        let mut vines = 0;
+        for chunk_x in -4..4 {
+            for chunk_z in -4..4 {
+                let chunk = generate_chunk(0, chunk_x, chunk_z);
+                for x in 0..16 {
+                    for z in 0..16 {
+                        for y in 0..256 {
+                            let block = chunk.block(x, y, z);
+                            let Some(facing) = block.facing() else {
+                                continue;
+                            };
+                            assert_eq!(block.unoriented(), Block::VINES);
+                            assert!(y as i32 >= SEA_LEVEL);
+                            let wall = ivec3(x as i32, y as i32, z as i32) - facing.direction();
+                            let wall = chunk.block(wall.x as usize, y, wall.z as usize);
+                            assert!(wall.is_solid());
+                            vines += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert!(vines > 0);
+    }
 }
@@ -10,11 +10,11 @@ pub struct PerlinNoise {
 }
 
 impl PerlinNoise {
-    pub fn new(chunk_pos: IVec2, scale: IVec3) -> Self {
-        let mut rand00 = Random::with_seed(seed_for_chunk(chunk_pos.x, chunk_pos.y));
-        let mut rand01 = Random::with_seed(seed_for_chunk(chunk_pos.x + 1, chunk_pos.y));
-        let mut rand10 = Random::with_seed(seed_for_chunk(chunk_pos.x, chunk_pos.y + 1));
-        let mut rand11 = Random::with_seed(seed_for_chunk(chunk_pos.x + 1, chunk_pos.y + 1));
+    pub fn new(seed: u64, chunk_pos: IVec2, scale: IVec3) -> Self {
+        let mut rand00 = Random::with_seed(seed_for_chunk(seed, chunk_pos.x, chunk_pos.y));
+        let mut rand01 = Random::with_seed(seed_for_chunk(seed, chunk_pos.x + 1, chunk_pos.y));
+        let mut rand10 = Random::with_seed(seed_for_chunk(seed, chunk_pos.x, chunk_pos.y + 1));
+        let mut rand11 = Random::with_seed(seed_for_chunk(seed, chunk_pos.x + 1, chunk_pos.y + 1));
 
         let mut points = Vec::new();
         for z in 0..2 * scale.z {
@@ -1,28 +1,130 @@
 use glam::{vec3, Vec3};
 
+const MULTIPLIER: u64 = 0x5_DEEC_E66D;
+const ADDEND: u64 = 0xB;
+const MASK: u64 = (1 << 48) - 1;
+
 /// Random number generator using the same algorithm as the Java `Random` class.
 ///
+/// Every method produces exactly the same sequence as its `java.util.Random` counterpart, so
+/// values can be checked against a JVM.
+///
 /// See also: https://docs.oracle.com/javase/8/docs/api/java/util/Random.html
 pub struct Random {
     seed: u64,
+    next_next_gaussian: Option<f64>,
 }
 
 impl Random {
     pub fn with_seed(seed: u64) -> Self {
         Self {
-            seed: seed ^ 0x5_DEEC_E66D & 0xFFFF_FFFF_FFFF,
+            seed: (seed ^ MULTIPLIER) & MASK,
+            next_next_gaussian: None,
+        }
+    }
+
+    fn next_bits(&mut self, bits: u32) -> i32 {
+        self.seed = (self.seed.wrapping_mul(MULTIPLIER).wrapping_add(ADDEND)) & MASK;
+        (self.seed >> (48 - bits)) as i32
+    }
+
+    /// Equivalent to `nextInt()`: uniformly distributed over all `i32` values.
+    pub fn next_int(&mut self) -> i32 {
+        self.next_bits(32)
+    }
+
+    /// Equivalent to `nextInt(bound)`: uniformly distributed in `0..bound`.
+    ///
+    /// Panics if `bound` is not positive.
+    pub fn next_int_bounded(&mut self, bound: i32) -> i32 {
+        assert!(bound > 0, "bound must be positive");
+
+        if bound & -bound == bound {
+            // Powers of two take the high bits, which are more random than the low ones.
+            return ((bound as i64 * self.next_bits(31) as i64) >> 31) as i32;
+        }
+
+        loop {
+            let bits = self.next_bits(31);
+            let value = bits % bound;
+            // Reject values from the final, incomplete multiple of `bound` to avoid bias.
+            if bits.wrapping_sub(value).wrapping_add(bound - 1) >= 0 {
+                return value;
+            }
         }
     }
 
-    fn next_bits(&mut self, bits: u32) -> u32 {
-        self.seed = (self.seed.wrapping_mul(0x5_DEEC_E66D) + 0xB) & 0xFFFF_FFFF_FFFF;
-        (self.seed >> (48 - bits)) as u32
+    /// Equivalent to `nextLong()`.
+    pub fn next_long(&mut self) -> i64 {
+        ((self.next_bits(32) as i64) << 32).wrapping_add(self.next_bits(32) as i64)
     }
 
+    /// Equivalent to `nextBoolean()`.
+    pub fn next_bool(&mut self) -> bool {
+        self.next_bits(1) != 0
+    }
+
+    /// Equivalent to `nextFloat()`: uniformly distributed in `0.0..1.0`.
     pub fn next_float(&mut self) -> f32 {
         self.next_bits(24) as f32 / (1 << 24) as f32
     }
 
+    /// Equivalent to `nextDouble()`: uniformly distributed in `0.0..1.0`.
+    pub fn next_double(&mut self) -> f64 {
+        let high = (self.next_bits(26) as i64) << 27;
+        let low = self.next_bits(27) as i64;
+        (high + low) as f64 / (1_i64 << 53) as f64
+    }
+
+    /// Equivalent to `nextGaussian()`: normally distributed with mean 0 and standard deviation 1.
+    pub fn next_gaussian(&mut self) -> f64 {
+        if let Some(gaussian) = self.next_next_gaussian.take() {
+            return gaussian;
+        }
+
+        // Marsaglia polar method, which produces two values at a time.
+        loop {
+            let v1 = 2.0 * self.next_double() - 1.0;
+            let v2 = 2.0 * self.next_double() - 1.0;
+            let s = v1 * v1 + v2 * v2;
+            if s < 1.0 && s != 0.0 {
+                let multiplier = (-2.0 * strict_ln(s) / s).sqrt();
+                self.next_next_gaussian = Some(v2 * multiplier);
+                return v1 * multiplier;
+            }
+        }
+    }
+
+    /// Shuffles `items` in place, in the same order as `Collections.shuffle(list, random)`.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.next_int_bounded(i as i32 + 1);
+            items.swap(i, j as usize);
+        }
+    }
+
+    /// Picks an index into `weights`, where each index is chosen with probability proportional
+    /// to its weight. Returns `None` if all of the weights are zero.
+    ///
+    /// Panics if the weights sum to more than `i32::MAX`.
+    pub fn choose_weighted(&mut self, weights: &[u32]) -> Option<usize> {
+        let total = weights.iter().map(|&w| w as u64).sum::<u64>();
+        if total == 0 {
+            return None;
+        }
+        let total = i32::try_from(total).expect("total weight must fit in an i32");
+
+        let mut target = self.next_int_bounded(total) as u32;
+        weights.iter().position(|&w| {
+            if target < w {
+                true
+            } else {
+                target -= w;
+                false
+            }
+        })
+    }
+
     pub fn next_normal_vec3(&mut self) -> Vec3 {
         vec3(
             self.next_float() * 2.0 - 1.0,
@@ -32,3 +134,176 @@ impl Random {
         .normalize()
     }
 }
+
+/// Natural logarithm using the fdlibm algorithm that backs Java's `StrictMath.log`, which can
+/// differ from the platform `ln` in the last bit. Only handles positive, normal inputs.
+fn strict_ln(x: f64) -> f64 {
+    const LN2_HI: f64 = f64::from_bits(0x3FE6_2E42_FEE0_0000);
+    const LN2_LO: f64 = f64::from_bits(0x3DEA_39EF_3579_3C76);
+    const LG1: f64 = f64::from_bits(0x3FE5_5555_5555_5593);
+    const LG2: f64 = f64::from_bits(0x3FD9_9999_9997_FA04);
+    const LG3: f64 = f64::from_bits(0x3FD2_4924_9422_9359);
+    const LG4: f64 = f64::from_bits(0x3FCC_71C5_1D8E_78AF);
+    const LG5: f64 = f64::from_bits(0x3FC7_4664_96CB_03DE);
+    const LG6: f64 = f64::from_bits(0x3FC3_9A09_D078_C69F);
+    const LG7: f64 = f64::from_bits(0x3FC2_F112_DF3E_5244);
+
+    debug_assert!(x.is_normal() && x > 0.0);
+
+    let bits = x.to_bits();
+    let hx = (bits >> 32) as i32;
+    let lx = bits & 0xFFFF_FFFF;
+
+    let mut k = (hx >> 20) - 1023;
+    let hx = hx & 0x000F_FFFF;
+    // Normalize x into [sqrt(2)/2, sqrt(2)).
+    let i = (hx + 0x9_5F64) & 0x10_0000;
+    let x = f64::from_bits(((hx | (i ^ 0x3FF0_0000)) as u64) << 32 | lx);
+    k += i >> 20;
+    let f = x - 1.0;
+    let dk = k as f64;
+
+    if (0x000F_FFFF & (2 + hx)) < 3 {
+        // |f| < 2^-20
+        if f == 0.0 {
+            return dk * LN2_HI + dk * LN2_LO;
+        }
+        let r = f * f * (0.5 - (1.0 / 3.0) * f);
+        return dk * LN2_HI - ((r - dk * LN2_LO) - f);
+    }
+
+    let s = f / (2.0 + f);
+    let z = s * s;
+    let w = z * z;
+    let t1 = w * (LG2 + w * (LG4 + w * LG6));
+    let t2 = z * (LG1 + w * (LG3 + w * (LG5 + w * LG7)));
+    let r = t2 + t1;
+
+    if ((hx - 0x6_147A) | (0x6_B851 - hx)) > 0 {
+        let hfsq = 0.5 * f * f;
+        dk * LN2_HI - ((hfsq - (s * (hfsq + r) + dk * LN2_LO)) - f)
+    } else {
+        dk * LN2_HI - ((s * (f - r) - dk * LN2_LO) - f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Random;
+
+    // Expected values were produced by `java.util.Random` on OpenJDK.
+
+    #[test]
+    fn next_int_matches_java() {
+        let mut random = Random::with_seed(0);
+        let values: Vec<_> = (0..5).map(|_| random.next_int()).collect();
+        assert_eq!(
+            values,
+            [
+                -1155484576,
+                -723955400,
+                1033096058,
+                -1690734402,
+                -1557280266
+            ]
+        );
+    }
+
+    #[test]
+    fn next_int_bounded_matches_java() {
+        let mut random = Random::with_seed(42);
+        let values: Vec<_> = (0..5).map(|_| random.next_int_bounded(10)).collect();
+        assert_eq!(values, [0, 3, 8, 4, 0]);
+
+        let mut random = Random::with_seed(42);
+        let values: Vec<_> = (0..5).map(|_| random.next_int_bounded(16)).collect();
+        assert_eq!(values, [11, 0, 10, 0, 4]);
+
+        let mut random = Random::with_seed(42);
+        let values: Vec<_> = (0..5)
+            .map(|_| random.next_int_bounded(1_000_000_007))
+            .collect();
+        assert_eq!(
+            values,
+            [562431123, 117392763, 467211241, 102948884, 662969970]
+        );
+    }
+
+    #[test]
+    fn next_long_matches_java() {
+        let mut random = Random::with_seed(-1_i64 as u64);
+        let values: Vec<_> = (0..4).map(|_| random.next_long()).collect();
+        assert_eq!(
+            values,
+            [
+                4961115982468162243,
+                226341162490527646,
+                -6233441030884181172,
+                7681931065131779340,
+            ]
+        );
+    }
+
+    #[test]
+    fn next_bool_matches_java() {
+        let mut random = Random::with_seed(7);
+        let values: Vec<_> = (0..8).map(|_| random.next_bool()).collect();
+        assert_eq!(values, [true, true, true, false, false, false, true, true]);
+    }
+
+    #[test]
+    fn next_float_matches_java() {
+        let mut random = Random::with_seed(12345);
+        let values: Vec<_> = (0..4).map(|_| random.next_float()).collect();
+        assert_eq!(values, [0.36180305, 0.5132095, 0.9329935, 0.9171147]);
+    }
+
+    #[test]
+    fn next_double_matches_java() {
+        let mut random = Random::with_seed(12345);
+        let values: Vec<_> = (0..4).map(|_| random.next_double()).collect();
+        assert_eq!(
+            values,
+            [
+                0.3618031071604718,
+                0.932993485288541,
+                0.8330913489710237,
+                0.32647575623792624,
+            ]
+        );
+    }
+
+    #[test]
+    fn next_gaussian_matches_java() {
+        let mut random = Random::with_seed(2024);
+        let values: Vec<_> = (0..5).map(|_| random.next_gaussian()).collect();
+        assert_eq!(
+            values,
+            [
+                0.4522047733159631,
+                -1.1899429517085636,
+                2.67950757630268,
+                -0.19279655155523512,
+                -0.07934819259025572,
+            ]
+        );
+    }
+
+    #[test]
+    fn shuffle_matches_java() {
+        let mut random = Random::with_seed(99);
+        let mut items: Vec<_> = (0..10).collect();
+        random.shuffle(&mut items);
+        assert_eq!(items, [8, 1, 4, 3, 9, 6, 0, 2, 5, 7]);
+    }
+
+    #[test]
+    fn choose_weighted_skips_zero_weights() {
+        let mut random = Random::with_seed(1);
+        for _ in 0..100 {
+            let index = random.choose_weighted(&[0, 3, 0, 1]).unwrap();
+            assert!(index == 1 || index == 3);
+        }
+        assert_eq!(random.choose_weighted(&[0, 0]), None);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, i32};
+use std::collections::BTreeMap;
 
 use block::Block;
 use glam::{ivec3, vec3, IVec3};
@@ -10,17 +10,25 @@ pub mod block;
 pub mod chunk;
 pub mod subchunk;
 
-mod generation;
+pub mod generation;
 
 pub const RENDER_DISTANCE: i32 = 4;
+/// The brightest light level, as seen under open sky.
+pub const MAX_LIGHT: u8 = 15;
 
 pub struct Terrain {
+    pub seed: u64,
     pub chunks: BTreeMap<(i32, i32), Chunk>,
 }
 
 impl Terrain {
     pub fn new() -> Self {
+        Self::with_seed(0)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
         Self {
+            seed,
             chunks: BTreeMap::new(),
         }
     }
@@ -46,7 +54,7 @@ impl Terrain {
             let dz = z.abs_diff(center_z);
             dx * dx + dz * dz
         }) {
-            self.chunks.insert((x, z), generate_chunk(x, z));
+            self.chunks.insert((x, z), generate_chunk(self.seed, x, z));
         }
 
         for &(x, y) in self.chunks.keys() {
@@ -80,6 +88,36 @@ impl Terrain {
             })
     }
 
+    /// Light from the sky reaching `block_pos`, which is blocked by anything solid above it.
+    pub fn sky_light(&self, block_pos: IVec3) -> u8 {
+        let chunk_x = block_pos.x.div_euclid(Subchunk::SIZE as i32);
+        let chunk_z = block_pos.z.div_euclid(Subchunk::SIZE as i32);
+        let Some(chunk) = self.chunks.get(&(chunk_x, chunk_z)) else {
+            return 0;
+        };
+
+        let top = (chunk.subchunks.len() * Subchunk::SIZE) as i32;
+        let covered = (block_pos.y + 1..top)
+            .any(|y| self.block(ivec3(block_pos.x, y, block_pos.z)).is_solid());
+        if covered {
+            0
+        } else {
+            MAX_LIGHT
+        }
+    }
+
+    /// The height of the highest solid block in the column at `x`, `z`, or `None` if the column
+    /// isn't loaded or is empty.
+    pub fn surface_height(&self, x: i32, z: i32) -> Option<i32> {
+        let chunk_x = x.div_euclid(Subchunk::SIZE as i32);
+        let chunk_z = z.div_euclid(Subchunk::SIZE as i32);
+        let chunk = self.chunks.get(&(chunk_x, chunk_z))?;
+        let top = (chunk.subchunks.len() * Subchunk::SIZE) as i32;
+        (0..top)
+            .rev()
+            .find(|&y| self.block(ivec3(x, y, z)).is_solid())
+    }
+
     pub fn set_block(&mut self, block_pos: IVec3, block: Block) {
         let subchunk_x = block_pos.x.div_euclid(Subchunk::SIZE as i32);
         let subchunk_y = block_pos.y.div_euclid(Subchunk::SIZE as i32);
@@ -111,6 +149,18 @@ impl Terrain {
             .and_then(|c| c.subchunks.get_mut(subchunk_pos.y as usize))
     }
 
+    /// Whether every chunk that `bounding_box` touches has been generated, so that its blocks
+    /// can be trusted rather than reading as air.
+    pub fn is_loaded(&self, bounding_box: BoundingBox) -> bool {
+        let min_chunk_x = (bounding_box.min.x.floor() as i32).div_euclid(Subchunk::SIZE as i32);
+        let max_chunk_x = (bounding_box.max.x.floor() as i32).div_euclid(Subchunk::SIZE as i32);
+        let min_chunk_z = (bounding_box.min.z.floor() as i32).div_euclid(Subchunk::SIZE as i32);
+        let max_chunk_z = (bounding_box.max.z.floor() as i32).div_euclid(Subchunk::SIZE as i32);
+
+        (min_chunk_x..=max_chunk_x)
+            .all(|x| (min_chunk_z..=max_chunk_z).all(|z| self.chunks.contains_key(&(x, z))))
+    }
+
     pub fn blocks_intersecting(
         &self,
         bounding_box: BoundingBox,
@@ -141,3 +191,44 @@ impl Terrain {
             .map(move |(&(x, z), chunk)| (x, z, chunk))
     }
 }
+
+impl Default for Terrain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+impl Terrain {
+    /// Empty, but generated, chunks at every chunk position in `xs` by `zs`.
+    pub fn empty(xs: std::ops::RangeInclusive<i32>, zs: std::ops::RangeInclusive<i32>) -> Self {
+        let mut terrain = Self::new();
+        for x in xs {
+            for z in zs.clone() {
+                let mut chunk = Chunk::new();
+                chunk.subchunks = vec![Subchunk::new(); 16];
+                terrain.chunks.insert((x, z), chunk);
+            }
+        }
+        terrain
+    }
+
+    /// Empty chunks as with [`Terrain::empty`], with a floor of `block` across all of them at
+    /// height `y`.
+    pub fn with_floor(
+        xs: std::ops::RangeInclusive<i32>,
+        zs: std::ops::RangeInclusive<i32>,
+        y: i32,
+        block: Block,
+    ) -> Self {
+        let mut terrain = Self::empty(xs, zs);
+        for chunk in terrain.chunks.values_mut() {
+            for x in 0..Subchunk::SIZE {
+                for z in 0..Subchunk::SIZE {
+                    chunk.set_block(x, y as usize, z, block);
+                }
+            }
+        }
+        terrain
+    }
+}
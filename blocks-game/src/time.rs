@@ -0,0 +1,160 @@
+use std::f32::consts::TAU;
+
+use glam::{vec3, Vec3};
+
+use crate::terrain::MAX_LIGHT;
+
+/// Seconds in a full day and night.
+pub const DAY_LENGTH: f32 = 1200.0;
+/// Times of day, as fractions of a day.
+pub const SUNRISE: f32 = 0.0;
+pub const NOON: f32 = 0.25;
+pub const SUNSET: f32 = 0.5;
+pub const MIDNIGHT: f32 = 0.75;
+/// How bright things are lit at night compared to during the day.
+const NIGHT_BRIGHTNESS: f32 = 0.2;
+/// How far below and above the horizon the sun is while it gets light or dark, as the sine of
+/// its angle.
+const TWILIGHT: f32 = 0.2;
+const DAY_SKY: Vec3 = vec3(0.4, 0.6, 0.9);
+const NIGHT_SKY: Vec3 = vec3(0.02, 0.02, 0.06);
+const SUNSET_SKY: Vec3 = vec3(0.9, 0.45, 0.2);
+/// How far a new world is into its first day.
+const STARTING_TIME: f32 = 0.1;
+
+/// The time of day, which moves the sun and changes how light it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldTime {
+    /// Days since the world began, including the fraction of the current day.
+    days: f64,
+    /// Whether time stands still.
+    pub frozen: bool,
+}
+
+impl Default for WorldTime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorldTime {
+    pub fn new() -> Self {
+        Self {
+            days: STARTING_TIME as f64,
+            frozen: false,
+        }
+    }
+
+    pub fn advance(&mut self, delta_time: f32) {
+        if !self.frozen {
+            self.days += (delta_time / DAY_LENGTH) as f64;
+        }
+    }
+
+    /// Whole days since the world began.
+    pub fn day(&self) -> u32 {
+        self.days as u32
+    }
+
+    /// How far through the current day it is, from 0 at sunrise to 1 at the next sunrise.
+    pub fn time_of_day(&self) -> f32 {
+        self.days.fract() as f32
+    }
+
+    /// Moves forwards to the next time it is `time_of_day`, which can be [`NOON`] or any other
+    /// fraction of a day.
+    pub fn set_time_of_day(&mut self, time_of_day: f32) {
+        let mut days = self.days.floor() + time_of_day.rem_euclid(1.0) as f64;
+        if days < self.days {
+            days += 1.0;
+        }
+        self.days = days;
+    }
+
+    /// Unit vector pointing towards the sun, which rises in +x and sets in -x.
+    pub fn sun_direction(&self) -> Vec3 {
+        let angle = self.time_of_day() * TAU;
+        vec3(angle.cos(), angle.sin(), -0.4).normalize()
+    }
+
+    /// How much of the sun's light is reaching the world, from 0 at night to 1 during the day.
+    fn sunlight(&self) -> f32 {
+        let height = (self.time_of_day() * TAU).sin();
+        let t = ((height + TWILIGHT) / (2.0 * TWILIGHT)).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    /// How brightly lit things in the open are, as a multiplier on their colour.
+    pub fn brightness(&self) -> f32 {
+        NIGHT_BRIGHTNESS + (1.0 - NIGHT_BRIGHTNESS) * self.sunlight()
+    }
+
+    /// The light level under open sky.
+    pub fn sky_light(&self) -> u8 {
+        (MAX_LIGHT as f32 * self.sunlight()).round() as u8
+    }
+
+    /// The colour of the sky, which distant things fade into.
+    pub fn sky_color(&self) -> Vec3 {
+        let height = (self.time_of_day() * TAU).sin();
+        let sky = NIGHT_SKY.lerp(DAY_SKY, self.sunlight());
+        let glow = (1.0 - height.abs() / TWILIGHT).max(0.0);
+        sky.lerp(SUNSET_SKY, 0.6 * glow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WorldTime, DAY_LENGTH, MIDNIGHT, NOON, SUNSET};
+    use crate::terrain::MAX_LIGHT;
+
+    #[test]
+    fn days_go_round() {
+        let mut time = WorldTime::new();
+        let start = time.time_of_day();
+
+        time.advance(DAY_LENGTH / 2.0);
+        time.advance(DAY_LENGTH / 2.0);
+
+        assert_eq!(time.day(), 1);
+        assert!((time.time_of_day() - start).abs() < 1e-4);
+    }
+
+    #[test]
+    fn nights_are_dark() {
+        let mut time = WorldTime::new();
+
+        time.set_time_of_day(NOON);
+        assert_eq!(time.sky_light(), MAX_LIGHT);
+        assert!(time.sun_direction().y > 0.9);
+        let noon_sky = time.sky_color();
+
+        time.set_time_of_day(MIDNIGHT);
+        assert_eq!(time.sky_light(), 0);
+        assert!(time.sun_direction().y < -0.9);
+        assert!(time.sky_color().length() < noon_sky.length());
+        assert!(time.brightness() < 1.0);
+    }
+
+    #[test]
+    fn setting_the_time_only_moves_forwards() {
+        let mut time = WorldTime::new();
+        time.set_time_of_day(SUNSET);
+        assert_eq!(time.day(), 0);
+
+        time.set_time_of_day(NOON);
+        assert_eq!(time.day(), 1);
+        assert!((time.time_of_day() - NOON).abs() < 1e-4);
+    }
+
+    #[test]
+    fn frozen_time_stands_still() {
+        let mut time = WorldTime::new();
+        time.frozen = true;
+        let start = time.clone();
+
+        time.advance(DAY_LENGTH / 3.0);
+
+        assert_eq!(time, start);
+    }
+}
@@ -0,0 +1,175 @@
+use glam::Vec3;
+
+use crate::terrain::generation::random::Random;
+
+/// Shortest and longest time each kind of weather lasts, in seconds.
+const MIN_CLEAR_TIME: f32 = 300.0;
+const MAX_CLEAR_TIME: f32 = 900.0;
+const MIN_RAIN_TIME: f32 = 120.0;
+const MAX_RAIN_TIME: f32 = 480.0;
+/// Chance that rain is a thunderstorm.
+const THUNDER_CHANCE: f32 = 0.3;
+/// Seconds it takes for rain to start or stop completely.
+const TRANSITION_TIME: f32 = 10.0;
+/// Average seconds between lightning strikes in a thunderstorm.
+const LIGHTNING_INTERVAL: f32 = 8.0;
+/// Seconds that a lightning strike stays lit.
+pub const LIGHTNING_TIME: f32 = 0.3;
+/// How much rain and thunderstorms darken the sky and its light.
+const RAIN_DARKENING: f32 = 0.3;
+const THUNDER_DARKENING: f32 = 0.6;
+const OVERCAST_SKY: Vec3 = Vec3::new(0.35, 0.37, 0.4);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeatherKind {
+    Clear,
+    Rain,
+    Thunder,
+}
+
+/// A bolt of lightning that has just hit the ground.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lightning {
+    /// Where it hit.
+    pub position: Vec3,
+    pub age: f32,
+}
+
+/// Rain and thunderstorms, which come and go over time. Whether rain falls as snow depends on the
+/// climate where it lands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Weather {
+    pub kind: WeatherKind,
+    /// Seconds until the weather changes.
+    pub remaining: f32,
+    /// How heavily it is raining, which fades in and out between 0 and 1.
+    intensity: f32,
+    pub lightning: Option<Lightning>,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Weather {
+    pub fn new() -> Self {
+        Self {
+            kind: WeatherKind::Clear,
+            remaining: MIN_CLEAR_TIME,
+            intensity: 0.0,
+            lightning: None,
+        }
+    }
+
+    /// Switches to `kind` for `duration` seconds.
+    pub fn set(&mut self, kind: WeatherKind, duration: f32) {
+        self.kind = kind;
+        self.remaining = duration;
+    }
+
+    pub fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    /// Whether anything is falling from the sky.
+    pub fn is_precipitating(&self) -> bool {
+        self.intensity > 0.0
+    }
+
+    /// Advances the weather by `delta_time` seconds. Returns whether lightning should strike.
+    pub fn update(&mut self, delta_time: f32, random: &mut Random) -> bool {
+        self.remaining -= delta_time;
+        if self.remaining <= 0.0 {
+            let (kind, min, max) = match self.kind {
+                WeatherKind::Clear if random.next_float() < THUNDER_CHANCE => {
+                    (WeatherKind::Thunder, MIN_RAIN_TIME, MAX_RAIN_TIME)
+                }
+                WeatherKind::Clear => (WeatherKind::Rain, MIN_RAIN_TIME, MAX_RAIN_TIME),
+                WeatherKind::Rain | WeatherKind::Thunder => {
+                    (WeatherKind::Clear, MIN_CLEAR_TIME, MAX_CLEAR_TIME)
+                }
+            };
+            self.set(kind, min + random.next_float() * (max - min));
+        }
+
+        let target = if self.kind == WeatherKind::Clear {
+            0.0
+        } else {
+            1.0
+        };
+        let step = delta_time / TRANSITION_TIME;
+        self.intensity += (target - self.intensity).clamp(-step, step);
+
+        if let Some(lightning) = &mut self.lightning {
+            lightning.age += delta_time;
+            if lightning.age >= LIGHTNING_TIME {
+                self.lightning = None;
+            }
+        }
+
+        self.kind == WeatherKind::Thunder
+            && self.intensity == 1.0
+            && random.next_float() < delta_time / LIGHTNING_INTERVAL
+    }
+
+    /// How much the weather takes away from the light of the sky, from 0 to 1.
+    pub fn darkening(&self) -> f32 {
+        let darkening = match self.kind {
+            WeatherKind::Thunder => THUNDER_DARKENING,
+            _ => RAIN_DARKENING,
+        };
+        darkening * self.intensity
+    }
+
+    /// `sky_color` as seen through the weather, greyed out by clouds and lit up by lightning.
+    pub fn tint_sky(&self, sky_color: Vec3) -> Vec3 {
+        let overcast = sky_color.lerp(OVERCAST_SKY * sky_color.length(), self.intensity);
+        let color = overcast * (1.0 - self.darkening());
+        match self.lightning {
+            Some(_) => color.lerp(Vec3::ONE, 0.5),
+            None => color,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Weather, WeatherKind, TRANSITION_TIME};
+    use crate::terrain::generation::random::Random;
+
+    #[test]
+    fn rain_fades_in_and_out() {
+        let mut weather = Weather::new();
+        let mut random = Random::with_seed(0);
+        weather.set(WeatherKind::Rain, 2.0 * TRANSITION_TIME);
+
+        weather.update(TRANSITION_TIME / 2.0, &mut random);
+        assert!(weather.is_precipitating());
+        assert!(weather.intensity() < 1.0);
+
+        weather.update(TRANSITION_TIME, &mut random);
+        assert_eq!(weather.intensity(), 1.0);
+
+        weather.update(TRANSITION_TIME, &mut random);
+        assert_eq!(weather.kind, WeatherKind::Clear);
+        weather.update(TRANSITION_TIME, &mut random);
+        assert!(!weather.is_precipitating());
+    }
+
+    #[test]
+    fn lightning_only_strikes_in_storms() {
+        let mut random = Random::with_seed(0);
+
+        let mut rain = Weather::new();
+        rain.set(WeatherKind::Rain, 1000.0);
+        let mut storm = Weather::new();
+        storm.set(WeatherKind::Thunder, 1000.0);
+
+        let rain_strikes = (0..6000).filter(|_| rain.update(0.1, &mut random)).count();
+        let storm_strikes = (0..6000).filter(|_| storm.update(0.1, &mut random)).count();
+        assert_eq!(rain_strikes, 0);
+        assert!(storm_strikes > 0);
+    }
+}
@@ -0,0 +1,252 @@
+use std::{cmp::Ordering, env, fs, process};
+
+use glam::ivec3;
+use image::{Rgb, RgbImage};
+
+use blocks_game::{
+    save::SaveData,
+    terrain::{
+        block::Block,
+        chunk::Chunk,
+        generation::{
+            climate::{self, Precipitation},
+            generate_chunk,
+        },
+        subchunk::Subchunk,
+    },
+};
+
+const USAGE: &str = "\
+Usage: blocks-map [OPTIONS]
+
+Renders a top-down map of the generated world, with x to the right and z downwards.
+
+Options:
+  --seed <SEED>         World seed [default: 0]
+  --world <PATH>        Take the seed from a world settings file saved by the game. Changes made
+                        to the world while playing aren't saved, so it's still shown as generated
+  --center <X>,<Z>      Block position at the centre of the map [default: 0,0]
+  --radius <CHUNKS>     Number of chunks to render either side of the centre, up to 32
+                        [default: 8]
+  --slice <Y>           Render a horizontal slice at height Y instead of the surface
+  --biomes              Colour the surface by its climate instead of by block
+  --output <PATH>       Where to write the PNG [default: map.png]";
+
+const CHUNK_HEIGHT: usize = 16 * Subchunk::SIZE;
+/// The largest `--radius`, which keeps the generated chunks to a few hundred megabytes.
+const MAX_RADIUS: i32 = 32;
+
+struct Options {
+    seed: u64,
+    center: (i32, i32),
+    radius: i32,
+    slice: Option<usize>,
+    biomes: bool,
+    output: String,
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    let map = Map::generate(&options);
+    let image = match options.slice {
+        Some(y) => map.render_slice(y),
+        None if options.biomes => map.render_surface(|x, z, height, block| match block {
+            Block::WATER => Rgb(block.color()),
+            _ => map.biome_color(x, z, height),
+        }),
+        None => map.render_surface(|_, _, _, block| Rgb(block.color())),
+    };
+
+    if let Err(err) = image.save(&options.output) {
+        eprintln!("Couldn't write {}: {err}", options.output);
+        process::exit(1);
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        seed: 0,
+        center: (0, 0),
+        radius: 8,
+        slice: None,
+        biomes: false,
+        output: "map.png".to_owned(),
+    };
+    let mut world = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "--seed" => options.seed = parse_number(&value()?)?,
+            "--world" => world = Some(value()?),
+            "--center" => {
+                let value = value()?;
+                let (x, z) = value
+                    .split_once(',')
+                    .ok_or_else(|| format!("Expected <X>,<Z> but got {value:?}"))?;
+                options.center = (parse_number(x)?, parse_number(z)?);
+            }
+            "--radius" => {
+                options.radius = parse_number(&value()?)?;
+                if !(0..=MAX_RADIUS).contains(&options.radius) {
+                    return Err(format!("Radius must be between 0 and {MAX_RADIUS}"));
+                }
+            }
+            "--slice" => {
+                let y = parse_number(&value()?)?;
+                if y >= CHUNK_HEIGHT {
+                    return Err(format!("Slice height must be below {CHUNK_HEIGHT}"));
+                }
+                options.slice = Some(y);
+            }
+            "--biomes" => options.biomes = true,
+            "--output" => options.output = value()?,
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                process::exit(0);
+            }
+            _ => return Err(format!("Unexpected argument {arg:?}")),
+        }
+    }
+
+    if options.slice.is_some() && options.biomes {
+        return Err("--slice and --biomes can't be used together".to_owned());
+    }
+    if let Some(path) = world {
+        let source =
+            fs::read_to_string(&path).map_err(|err| format!("Couldn't read {path}: {err}"))?;
+        let data =
+            SaveData::parse(&source).map_err(|err| format!("Couldn't read {path}, {err}"))?;
+        options.seed = data.seed;
+    }
+
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(s: &str) -> Result<T, String> {
+    s.trim()
+        .parse()
+        .map_err(|_| format!("Expected a number but got {s:?}"))
+}
+
+/// A square region of generated chunks, indexed by block column.
+struct Map {
+    seed: u64,
+    /// World x and z of the block column in the top left corner.
+    origin: (i32, i32),
+    /// Width and height of the map in blocks.
+    size: usize,
+    chunks: Vec<Chunk>,
+}
+
+impl Map {
+    fn generate(options: &Options) -> Self {
+        let center_x = options.center.0.div_euclid(Subchunk::SIZE as i32);
+        let center_z = options.center.1.div_euclid(Subchunk::SIZE as i32);
+        let width = 2 * options.radius + 1;
+
+        let mut chunks = Vec::new();
+        for z in 0..width {
+            for x in 0..width {
+                let chunk_x = center_x - options.radius + x;
+                let chunk_z = center_z - options.radius + z;
+                chunks.push(generate_chunk(options.seed, chunk_x, chunk_z));
+            }
+        }
+
+        let size = Subchunk::SIZE as i32;
+        Self {
+            seed: options.seed,
+            origin: (
+                (center_x - options.radius) * size,
+                (center_z - options.radius) * size,
+            ),
+            size: width as usize * Subchunk::SIZE,
+            chunks,
+        }
+    }
+
+    fn block(&self, x: usize, y: usize, z: usize) -> Block {
+        let chunks_wide = self.size / Subchunk::SIZE;
+        let chunk = &self.chunks[z / Subchunk::SIZE * chunks_wide + x / Subchunk::SIZE];
+        chunk.block(x % Subchunk::SIZE, y, z % Subchunk::SIZE)
+    }
+
+    /// Returns the height and type of the highest non-air block in a column.
+    fn surface(&self, x: usize, z: usize) -> Option<(usize, Block)> {
+        (0..CHUNK_HEIGHT)
+            .rev()
+            .map(|y| (y, self.block(x, y, z)))
+            .find(|&(_, block)| block != Block::AIR)
+    }
+
+    /// Renders the highest block in each column, coloured by `color` given the column's x and z,
+    /// and the height and type of the block.
+    fn render_surface(&self, color: impl Fn(usize, usize, usize, Block) -> Rgb<u8>) -> RgbImage {
+        let heights: Vec<_> = (0..self.size)
+            .flat_map(|z| (0..self.size).map(move |x| (x, z)))
+            .map(|(x, z)| self.surface(x, z))
+            .collect();
+
+        RgbImage::from_fn(self.size as u32, self.size as u32, |x, z| {
+            let (x, z) = (x as usize, z as usize);
+            let Some((height, block)) = heights[z * self.size + x] else {
+                return Rgb([0, 0, 0]);
+            };
+
+            // Lighter when higher up, with hill shading from the slope towards -z.
+            let mut brightness = 0.6 + 0.6 * height as f32 / CHUNK_HEIGHT as f32;
+            if z > 0 {
+                if let Some((previous, _)) = heights[(z - 1) * self.size + x] {
+                    brightness *= match height.cmp(&previous) {
+                        Ordering::Greater => 1.15,
+                        Ordering::Less => 0.85,
+                        Ordering::Equal => 1.0,
+                    };
+                }
+            }
+
+            shade(color(x, z, height, block), brightness)
+        })
+    }
+
+    /// The colour of the climate at the top of a column, with snowy places white, dry places
+    /// sandy and the rest green.
+    fn biome_color(&self, x: usize, z: usize, height: usize) -> Rgb<u8> {
+        let position = ivec3(
+            self.origin.0 + x as i32,
+            height as i32,
+            self.origin.1 + z as i32,
+        );
+        match climate::precipitation(self.seed, position) {
+            Precipitation::Snow => Rgb([230, 235, 245]),
+            Precipitation::Rain => Rgb([95, 159, 53]),
+            Precipitation::None => Rgb([219, 199, 138]),
+        }
+    }
+
+    fn render_slice(&self, y: usize) -> RgbImage {
+        RgbImage::from_fn(self.size as u32, self.size as u32, |x, z| {
+            match self.block(x as usize, y, z as usize) {
+                Block::AIR => Rgb([0, 0, 0]),
+                block => Rgb(block.color()),
+            }
+        })
+    }
+}
+
+fn shade(color: Rgb<u8>, brightness: f32) -> Rgb<u8> {
+    Rgb(color
+        .0
+        .map(|c| (c as f32 * brightness).clamp(0.0, 255.0) as u8))
+}
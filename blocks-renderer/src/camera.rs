@@ -1,6 +1,6 @@
 use std::f32;
 
-use blocks_game::player::Player;
+use blocks_game::Game;
 use glam::{Mat4, Vec3};
 
 pub struct Camera {
@@ -11,6 +11,9 @@ pub struct Camera {
     fovy: f32,
     znear: f32,
     zfar: f32,
+    sun_direction: Vec3,
+    brightness: f32,
+    sky_color: Vec3,
 }
 
 #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
@@ -19,6 +22,10 @@ pub struct CameraBufferContents {
     view_projection: [f32; 4 * 4],
     position: [f32; 3],
     aspect: f32,
+    sun_direction: [f32; 3],
+    brightness: f32,
+    sky_color: [f32; 3],
+    _padding: f32,
 }
 
 impl Camera {
@@ -31,13 +38,20 @@ impl Camera {
             fovy: 45.0,
             znear: 0.1,
             zfar: 100.0,
+            sun_direction: Vec3::Y,
+            brightness: 1.0,
+            sky_color: Vec3::ZERO,
         }
     }
 
-    pub fn update(&mut self, player: &Player) {
-        self.eye = player.head_position();
-        self.dir = player.looking_direction();
-        self.up = player.up_direction();
+    pub fn update(&mut self, game: &Game) {
+        self.eye = game.interpolated_head_position();
+        // Turning the head isn't tied to ticks, so the look direction is already up to date.
+        self.dir = game.player.looking_direction();
+        self.up = game.player.up_direction();
+        self.sun_direction = game.time.sun_direction();
+        self.brightness = game.brightness();
+        self.sky_color = game.sky_color();
     }
 
     fn build_view_projection_matrix(&self) -> Mat4 {
@@ -52,6 +66,10 @@ impl Camera {
             view_projection: self.build_view_projection_matrix().to_cols_array(),
             position: self.eye.to_array(),
             aspect: self.aspect,
+            sun_direction: self.sun_direction.to_array(),
+            brightness: self.brightness,
+            sky_color: self.sky_color.to_array(),
+            _padding: 0.0,
         }
     }
 }
@@ -0,0 +1,60 @@
+// Vertex shader
+
+const CRACK_COLOR: vec4<f32> = vec4<f32>(0.0, 0.0, 0.0, 0.6);
+// Number of crack pixels along each edge of a block face.
+const RESOLUTION: f32 = 8.0;
+// How far the overlay sticks out from the block, so that it doesn't z-fight with it.
+const INFLATION: f32 = 0.002;
+
+struct Camera {
+    matrix: mat4x4<f32>,
+    _position: vec3<f32>,
+    _aspect: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct Crack {
+    position: vec3<f32>,
+    progress: f32,
+}
+
+@group(1) @binding(0)
+var<uniform> crack: Crack;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) block_position: vec3<f32>,
+};
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let position = (model.position - 0.5) * (1.0 + 2.0 * INFLATION) + 0.5;
+    out.clip_position = camera.matrix * vec4(crack.position + position, 1.0);
+    out.block_position = position;
+    return out;
+}
+
+// Fragment shader
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Each face lies on a different plane, so the cells on different faces never line up.
+    let cell = floor(in.block_position * RESOLUTION);
+    if (hash(cell) >= crack.progress) {
+        discard;
+    }
+    return CRACK_COLOR;
+}
+
+fn hash(p: vec3<f32>) -> f32 {
+    return fract(sin(dot(p, vec3<f32>(12.9898, 78.233, 37.719))) * 43758.5453);
+}
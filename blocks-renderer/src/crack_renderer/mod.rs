@@ -0,0 +1,207 @@
+use std::mem;
+
+use glam::Vec3;
+use wgpu::util::DeviceExt;
+
+use blocks_game::mining::Mining;
+
+use crate::texture;
+
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct Vertex {
+    position: Vec3,
+}
+
+impl Vertex {
+    const ATTRIBS: [wgpu::VertexAttribute; 1] = wgpu::vertex_attr_array![0 => Float32x3];
+
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct CrackBufferContents {
+    position: [f32; 3],
+    progress: f32,
+}
+
+/// Draws cracks over the block being mined, spreading as it gets closer to breaking.
+pub struct CrackRenderer {
+    render_pipeline: wgpu::RenderPipeline,
+    vertex_buffer: wgpu::Buffer,
+    crack_buffer: wgpu::Buffer,
+    crack_bind_group: wgpu::BindGroup,
+    visible: bool,
+}
+
+impl CrackRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        color_target_format: wgpu::TextureFormat,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("crack_shader.wgsl"));
+
+        let crack_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Crack Buffer"),
+            contents: bytemuck::cast_slice(&[CrackBufferContents {
+                position: [0.0; 3],
+                progress: 0.0,
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let crack_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+                label: Some("crack_bind_group_layout"),
+            });
+
+        let crack_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &crack_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: crack_buffer.as_entire_binding(),
+            }],
+            label: Some("crack_bind_group"),
+        });
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Crack Render Pipeline Layout"),
+                bind_group_layouts: &[camera_bind_group_layout, &crack_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Crack Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: color_target_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: texture::Texture::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Crack Vertex Buffer"),
+            contents: bytemuck::cast_slice(&cube_vertices()),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        Self {
+            render_pipeline,
+            vertex_buffer,
+            crack_buffer,
+            crack_bind_group,
+            visible: false,
+        }
+    }
+
+    pub fn update(&mut self, queue: &wgpu::Queue, mining: &Mining) {
+        self.visible = false;
+        let Some(target) = mining.target() else {
+            return;
+        };
+        if mining.progress() <= 0.0 {
+            return;
+        }
+
+        queue.write_buffer(
+            &self.crack_buffer,
+            0,
+            bytemuck::cast_slice(&[CrackBufferContents {
+                position: target.as_vec3().to_array(),
+                progress: mining.progress(),
+            }]),
+        );
+        self.visible = true;
+    }
+
+    pub fn render(&self, render_pass: &mut wgpu::RenderPass, camera_bind_group: &wgpu::BindGroup) {
+        if !self.visible {
+            return;
+        }
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, camera_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.crack_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.draw(0..36, 0..1);
+    }
+}
+
+/// Two triangles for each face of a unit cube.
+fn cube_vertices() -> Vec<Vertex> {
+    let mut vertices = Vec::with_capacity(36);
+    for axis in 0..3 {
+        for side in [0.0, 1.0] {
+            let corner = |u: f32, v: f32| {
+                let mut position = Vec3::ZERO;
+                position[axis] = side;
+                position[(axis + 1) % 3] = u;
+                position[(axis + 2) % 3] = v;
+                Vertex { position }
+            };
+            vertices.extend([
+                corner(0.0, 0.0),
+                corner(1.0, 0.0),
+                corner(1.0, 1.0),
+                corner(0.0, 0.0),
+                corner(1.0, 1.0),
+                corner(0.0, 1.0),
+            ]);
+        }
+    }
+    vertices
+}
@@ -0,0 +1,114 @@
+use glam::{vec2, Vec2};
+
+use blocks_game::{
+    crafting::{CraftingScreen, Slot, GRID_SIZE},
+    inventory::{ItemStack, HOTBAR_SIZE, INVENTORY_SIZE},
+    Game,
+};
+
+use super::{
+    hotbar::{self, SLOT_COLOR, SLOT_SIZE, SLOT_SPACING},
+    quads::QuadBatch,
+};
+
+/// Distance from the bottom left corner of one slot to the next.
+const SLOT_STEP: f32 = SLOT_SIZE + SLOT_SPACING;
+/// Bottom left corner of the bottom left slot of the inventory, which is the first hotbar slot.
+const INVENTORY_CORNER: Vec2 = vec2(-4.5 * SLOT_STEP, -0.75);
+/// Gap between the hotbar and the rest of the inventory above it.
+const HOTBAR_GAP: f32 = 0.04;
+/// Bottom left corner of the bottom left slot of the crafting grid.
+const GRID_CORNER: Vec2 = vec2(-3.5 * SLOT_STEP, -0.05);
+const RESULT_CORNER: Vec2 = vec2(1.5 * SLOT_STEP, -0.05 + SLOT_STEP);
+const PANEL_MARGIN: f32 = 0.05;
+
+const BACKDROP_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.4];
+const PANEL_COLOR: [f32; 4] = [0.3, 0.3, 0.3, 0.9];
+const ARROW_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 1.0];
+const HOVER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.3];
+
+/// Every slot on the crafting screen, with the bottom left corner of where it's drawn.
+fn slots() -> impl Iterator<Item = (Slot, Vec2)> {
+    let inventory = (0..INVENTORY_SIZE).map(|i| {
+        let column = (i % HOTBAR_SIZE) as f32;
+        let row = (i / HOTBAR_SIZE) as f32;
+        // The rest of the inventory is above the hotbar, starting from the top row.
+        let y = if i < HOTBAR_SIZE {
+            0.0
+        } else {
+            HOTBAR_GAP + (INVENTORY_SIZE / HOTBAR_SIZE) as f32 * SLOT_STEP - row * SLOT_STEP
+        };
+        (
+            Slot::Inventory(i),
+            INVENTORY_CORNER + vec2(column * SLOT_STEP, y),
+        )
+    });
+    let grid = (0..GRID_SIZE * GRID_SIZE).map(|i| {
+        let column = (i % GRID_SIZE) as f32;
+        let row = (GRID_SIZE - 1 - i / GRID_SIZE) as f32;
+        (Slot::Grid(i), GRID_CORNER + SLOT_STEP * vec2(column, row))
+    });
+    inventory.chain(grid).chain([(Slot::Result, RESULT_CORNER)])
+}
+
+/// The slot under `position`, in HUD coordinates.
+pub fn slot_at(position: Vec2) -> Option<Slot> {
+    slots()
+        .find(|(_, min)| {
+            let max = *min + SLOT_SIZE;
+            position.cmpge(*min).all() && position.cmplt(max).all()
+        })
+        .map(|(slot, _)| slot)
+}
+
+/// Adds the crafting grid and the inventory over the world, with the stack being moved following
+/// the cursor.
+pub fn add_crafting_screen(quads: &mut QuadBatch, game: &Game, cursor: Vec2) {
+    let Some(screen) = &game.crafting else {
+        return;
+    };
+
+    // Wide enough to cover the screen at any sensible aspect ratio.
+    quads.add(vec2(-16.0, -1.0), vec2(16.0, 1.0), BACKDROP_COLOR);
+    let (min, max) = slots().fold(
+        (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+        |(min, max), (_, corner)| (min.min(corner), max.max(corner + SLOT_SIZE)),
+    );
+    quads.add(min - PANEL_MARGIN, max + PANEL_MARGIN, PANEL_COLOR);
+
+    let arrow_y = RESULT_CORNER.y + SLOT_SIZE / 2.0;
+    quads.add(
+        vec2(-0.5 * SLOT_STEP, arrow_y - 0.01),
+        vec2(0.5 * SLOT_STEP, arrow_y + 0.01),
+        ARROW_COLOR,
+    );
+    quads.add(
+        vec2(0.5 * SLOT_STEP, arrow_y - 0.03),
+        vec2(0.5 * SLOT_STEP + 0.03, arrow_y + 0.03),
+        ARROW_COLOR,
+    );
+
+    let hovered = slot_at(cursor);
+    for (slot, min) in slots() {
+        quads.add(min, min + SLOT_SIZE, SLOT_COLOR);
+        if hovered == Some(slot) {
+            quads.add(min, min + SLOT_SIZE, HOVER_COLOR);
+        }
+        if let Some(stack) = stack_in(slot, screen, game) {
+            hotbar::add_stack(quads, stack, min, true);
+        }
+    }
+
+    if let Some(held) = screen.held {
+        hotbar::add_stack(quads, held, cursor - SLOT_SIZE / 2.0, true);
+    }
+}
+
+/// What is shown in `slot`, which for the result slot is what would be crafted.
+fn stack_in(slot: Slot, screen: &CraftingScreen, game: &Game) -> Option<ItemStack> {
+    match slot {
+        Slot::Inventory(i) => game.player.inventory.slots[i],
+        Slot::Grid(i) => screen.grid.slots[i],
+        Slot::Result => screen.grid.result(&game.recipes),
+    }
+}
@@ -0,0 +1,128 @@
+use glam::{vec2, Vec2};
+
+use blocks_game::{
+    inventory::{Inventory, ItemStack, HOTBAR_SIZE},
+    item::{Item, ToolKind},
+};
+
+use super::quads::QuadBatch;
+use crate::item_colors::{item_color, tier_color};
+
+pub(super) const SLOT_SIZE: f32 = 0.14;
+pub(super) const SLOT_SPACING: f32 = 0.01;
+/// Gap between the edge of a slot and the item inside it.
+const ICON_INSET: f32 = 0.025;
+const SELECTION_BORDER: f32 = 0.01;
+const BOTTOM: f32 = -0.97;
+/// The top edge of the hotbar, so other parts of the HUD can sit above it.
+pub const TOP: f32 = BOTTOM + SLOT_SIZE;
+
+pub(super) const SLOT_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.5];
+const SELECTION_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.9];
+const COUNT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const HANDLE_COLOR: [f32; 4] = [0.45, 0.3, 0.15, 1.0];
+
+/// Size of one pixel of the digit font.
+const DIGIT_PIXEL: f32 = 0.008;
+/// 3x5 pixel digits, one row per entry from the top, with the high bit on the left.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Adds the hotbar along the bottom of the screen, with the selected slot outlined and stack
+/// sizes in the corner of each slot.
+pub fn add_hotbar(quads: &mut QuadBatch, inventory: &Inventory) {
+    let width = HOTBAR_SIZE as f32 * (SLOT_SIZE + SLOT_SPACING) - SLOT_SPACING;
+
+    for (i, stack) in inventory.hotbar().iter().enumerate() {
+        let min = vec2(-width / 2.0 + i as f32 * (SLOT_SIZE + SLOT_SPACING), BOTTOM);
+        let max = min + SLOT_SIZE;
+
+        if i == inventory.selected_slot() {
+            quads.add(
+                min - SELECTION_BORDER,
+                max + SELECTION_BORDER,
+                SELECTION_COLOR,
+            );
+        }
+        quads.add(min, max, SLOT_COLOR);
+
+        if let Some(stack) = stack {
+            add_stack(quads, *stack, min, !inventory.infinite);
+        }
+    }
+}
+
+/// Adds the icon for `stack` in the slot with its bottom left corner at `min`, with the size of
+/// the stack in the corner if `show_count` is set.
+pub(super) fn add_stack(quads: &mut QuadBatch, stack: ItemStack, min: Vec2, show_count: bool) {
+    let max = min + SLOT_SIZE;
+    add_icon(quads, stack.item, min + ICON_INSET, max - ICON_INSET);
+    if stack.count > 1 && show_count {
+        add_number(quads, stack.count, vec2(max.x, min.y) + vec2(-0.01, 0.01));
+    }
+}
+
+/// Adds `number` with its bottom right corner at `corner`.
+fn add_number(quads: &mut QuadBatch, number: u32, corner: Vec2) {
+    let mut right = corner.x;
+    let mut remaining = number;
+    loop {
+        add_digit(
+            quads,
+            (remaining % 10) as usize,
+            vec2(right - 3.0 * DIGIT_PIXEL, corner.y),
+        );
+        right -= 4.0 * DIGIT_PIXEL;
+        remaining /= 10;
+        if remaining == 0 {
+            break;
+        }
+    }
+}
+
+fn add_digit(quads: &mut QuadBatch, digit: usize, bottom_left: Vec2) {
+    for (row, bits) in DIGITS[digit].iter().enumerate() {
+        for column in 0..3 {
+            if bits & (0b100 >> column) != 0 {
+                let min = bottom_left + DIGIT_PIXEL * vec2(column as f32, 4.0 - row as f32);
+                quads.add(min, min + DIGIT_PIXEL, COUNT_COLOR);
+            }
+        }
+    }
+}
+
+fn add_icon(quads: &mut QuadBatch, item: Item, min: Vec2, max: Vec2) {
+    let Item::Tool(kind, tier) = item else {
+        quads.add(min, max, item_color(item));
+        return;
+    };
+
+    // A handle up the middle with the head across the top, wide for pickaxes and narrow for
+    // shovels.
+    let size = max - min;
+    let center = (min.x + max.x) / 2.0;
+    quads.add(
+        vec2(center - size.x / 12.0, min.y),
+        vec2(center + size.x / 12.0, max.y),
+        HANDLE_COLOR,
+    );
+    let head_width = match kind {
+        ToolKind::Pickaxe => size.x,
+        ToolKind::Shovel => size.x / 3.0,
+    };
+    quads.add(
+        vec2(center - head_width / 2.0, max.y - size.y / 4.0),
+        vec2(center + head_width / 2.0, max.y),
+        tier_color(tier),
+    );
+}
@@ -1,9 +1,18 @@
 use std::mem;
 
 use glam::{vec2, Vec2};
+use quads::{QuadBatch, Quads};
 use wgpu::util::DeviceExt;
 
-use crate::texture;
+use blocks_game::{game_mode::GameMode, Game};
+
+use crate::{texture, touch_controls::TouchControls};
+
+pub mod crafting;
+mod hotbar;
+mod quads;
+mod status_bars;
+mod touch_controls;
 
 #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 #[repr(C)]
@@ -44,6 +53,7 @@ pub struct HudRenderer {
     crosshairs_bind_group: wgpu::BindGroup,
     render_pipeline: wgpu::RenderPipeline,
     vertex_buffer: wgpu::Buffer,
+    quads: Quads,
 }
 
 impl HudRenderer {
@@ -103,7 +113,7 @@ impl HudRenderer {
         let render_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("HUD Render Pipeline Layout"),
-                bind_group_layouts: &[&camera_bind_group_layout, &texture_bind_group_layout],
+                bind_group_layouts: &[camera_bind_group_layout, &texture_bind_group_layout],
                 push_constant_ranges: &[],
             });
 
@@ -157,11 +167,35 @@ impl HudRenderer {
             usage: wgpu::BufferUsages::VERTEX,
         });
 
+        let quads = Quads::new(device, camera_bind_group_layout, color_target_format);
+
         Self {
             crosshairs_bind_group,
             render_pipeline,
             vertex_buffer,
+            quads,
+        }
+    }
+
+    /// Rebuilds the HUD, with `cursor` being where the mouse is in HUD coordinates, on a screen
+    /// `aspect` wide.
+    pub fn update(
+        &mut self,
+        queue: &wgpu::Queue,
+        game: &Game,
+        cursor: Vec2,
+        touch_controls: &TouchControls,
+        aspect: f32,
+    ) {
+        let mut batch = QuadBatch::default();
+        hotbar::add_hotbar(&mut batch, &game.player.inventory);
+        if game.game_mode() == GameMode::Survival {
+            status_bars::add_status_bars(&mut batch, &game.player);
         }
+        status_bars::add_death_tint(&mut batch, &game.player);
+        touch_controls::add_touch_controls(&mut batch, touch_controls, aspect);
+        crafting::add_crafting_screen(&mut batch, game, cursor);
+        self.quads.update(queue, &batch);
     }
 
     pub fn render(&self, render_pass: &mut wgpu::RenderPass, camera_bind_group: &wgpu::BindGroup) {
@@ -170,5 +204,7 @@ impl HudRenderer {
         render_pass.set_bind_group(1, &self.crosshairs_bind_group, &[]);
         render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
         render_pass.draw(0..3, 0..1);
+
+        self.quads.render(render_pass, camera_bind_group);
     }
 }
@@ -0,0 +1,42 @@
+use glam::vec2;
+
+use blocks_game::player::Player;
+
+use super::{hotbar, quads::QuadBatch};
+
+const BAR_WIDTH: f32 = 0.8;
+const BAR_HEIGHT: f32 = 0.04;
+const BAR_SPACING: f32 = 0.02;
+
+const BACKGROUND_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.5];
+const HEALTH_COLOR: [f32; 4] = [0.8, 0.1, 0.1, 1.0];
+const FOOD_COLOR: [f32; 4] = [0.8, 0.5, 0.2, 1.0];
+const OXYGEN_COLOR: [f32; 4] = [0.2, 0.5, 1.0, 1.0];
+const DEATH_COLOR: [f32; 4] = [0.6, 0.0, 0.0, 0.5];
+
+/// Adds health, food and oxygen bars just above the hotbar.
+pub fn add_status_bars(quads: &mut QuadBatch, player: &Player) {
+    let mut bar_bottom = hotbar::TOP + BAR_SPACING;
+    let mut add_bar = |fraction: f32, color: [f32; 4]| {
+        let min = vec2(-BAR_WIDTH / 2.0, bar_bottom);
+        let max = vec2(BAR_WIDTH / 2.0, bar_bottom + BAR_HEIGHT);
+        quads.add(min, max, BACKGROUND_COLOR);
+        let filled = min.x + BAR_WIDTH * fraction.clamp(0.0, 1.0);
+        quads.add(min, vec2(filled, max.y), color);
+        bar_bottom += BAR_HEIGHT + BAR_SPACING;
+    };
+
+    add_bar(player.health_fraction(), HEALTH_COLOR);
+    add_bar(player.hunger.food_fraction(), FOOD_COLOR);
+    if player.oxygen_fraction() < 1.0 {
+        add_bar(player.oxygen_fraction(), OXYGEN_COLOR);
+    }
+}
+
+/// Tints the whole screen red while the player is dead, whatever the game mode.
+pub fn add_death_tint(quads: &mut QuadBatch, player: &Player) {
+    if player.is_dead() {
+        // Wide enough to cover the screen at any sensible aspect ratio.
+        quads.add(vec2(-16.0, -1.0), vec2(16.0, 1.0), DEATH_COLOR);
+    }
+}
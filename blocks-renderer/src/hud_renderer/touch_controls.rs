@@ -0,0 +1,68 @@
+use glam::{vec2, Vec2};
+
+use crate::touch_controls::{TouchControls, JOYSTICK_RADIUS, JUMP_RADIUS};
+
+use super::quads::QuadBatch;
+
+/// Number of strips a circle is drawn with.
+const CIRCLE_STRIPS: usize = 12;
+const KNOB_RADIUS: f32 = 0.08;
+const ARROW_SIZE: f32 = 0.05;
+
+const BASE_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.3];
+const KNOB_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+const BUTTON_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.3];
+const PRESSED_BUTTON_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.3];
+const ARROW_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.6];
+
+/// Adds the joystick and jump button if the touch controls are being used, on a screen `aspect`
+/// wide.
+pub fn add_touch_controls(quads: &mut QuadBatch, controls: &TouchControls, aspect: f32) {
+    if !controls.active {
+        return;
+    }
+
+    let joystick = TouchControls::joystick_center(aspect);
+    add_circle(quads, joystick, JOYSTICK_RADIUS, BASE_COLOR);
+    add_circle(
+        quads,
+        joystick + controls.knob_offset(),
+        KNOB_RADIUS,
+        KNOB_COLOR,
+    );
+
+    let jump = TouchControls::jump_center(aspect);
+    let color = if controls.jump_held() {
+        PRESSED_BUTTON_COLOR
+    } else {
+        BUTTON_COLOR
+    };
+    add_circle(quads, jump, JUMP_RADIUS, color);
+    // An upwards arrow, narrowing towards the top.
+    for i in 0..CIRCLE_STRIPS {
+        let bottom = i as f32 / CIRCLE_STRIPS as f32;
+        let top = (i + 1) as f32 / CIRCLE_STRIPS as f32;
+        let half_width = ARROW_SIZE * (1.0 - bottom);
+        quads.add(
+            jump + vec2(-half_width, ARROW_SIZE * (2.0 * bottom - 1.0)),
+            jump + vec2(half_width, ARROW_SIZE * (2.0 * top - 1.0)),
+            ARROW_COLOR,
+        );
+    }
+}
+
+/// Adds a circle made of horizontal strips.
+fn add_circle(quads: &mut QuadBatch, center: Vec2, radius: f32, color: [f32; 4]) {
+    for i in 0..CIRCLE_STRIPS {
+        let bottom = -1.0 + 2.0 * i as f32 / CIRCLE_STRIPS as f32;
+        let top = -1.0 + 2.0 * (i + 1) as f32 / CIRCLE_STRIPS as f32;
+        // Sized to the edge of the circle at the middle of the strip.
+        let middle = (bottom + top) / 2.0;
+        let half_width = radius * (1.0 - middle * middle).sqrt();
+        quads.add(
+            center + vec2(-half_width, radius * bottom),
+            center + vec2(half_width, radius * top),
+            color,
+        );
+    }
+}
@@ -0,0 +1,529 @@
+use std::fmt;
+
+use glam::{vec2, Vec2};
+use winit::{
+    dpi::PhysicalPosition,
+    event::{MouseButton, Touch, TouchPhase},
+    keyboard::KeyCode,
+};
+
+use blocks_game::{inventory::HOTBAR_SIZE, parse_error::ParseError};
+
+/// The bindings that come with the game, in the format of a bindings file.
+pub const DEFAULT_BINDINGS: &str = include_str!("../../assets/bindings.txt");
+/// Seconds a touch has to stay in place to count as a long press.
+const LONG_PRESS_TIME: f32 = 0.5;
+/// Pixels a touch can move and still count as a tap or long press.
+const TOUCH_SLOP: f64 = 10.0;
+
+/// Something the player can do, which any number of inputs can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    /// Jumps, swims or flies up, and respawns when dead.
+    Jump,
+    /// Sneaks, or flies down.
+    Sneak,
+    Sprint,
+    /// Breaks blocks and attacks mobs, or picks up stacks on the crafting screen.
+    Break,
+    /// Places blocks and eats food, or puts down single items on the crafting screen.
+    Place,
+    /// Opens and closes the crafting screen.
+    Crafting,
+    /// Lets go of the mouse and closes any open screen.
+    ReleaseCursor,
+    ToggleFlying,
+    ToggleNoclip,
+    ToggleSpectator,
+    NextTimeOfDay,
+    FreezeTime,
+    NextWeather,
+    /// Switches between survival and creative.
+    NextGameMode,
+    /// Selects a hotbar slot, counting from 0.
+    Hotbar(usize),
+}
+
+impl Action {
+    /// Every action there is.
+    pub fn all() -> impl Iterator<Item = Self> {
+        [
+            Self::MoveForward,
+            Self::MoveBackward,
+            Self::MoveLeft,
+            Self::MoveRight,
+            Self::Jump,
+            Self::Sneak,
+            Self::Sprint,
+            Self::Break,
+            Self::Place,
+            Self::Crafting,
+            Self::ReleaseCursor,
+            Self::ToggleFlying,
+            Self::ToggleNoclip,
+            Self::ToggleSpectator,
+            Self::NextTimeOfDay,
+            Self::FreezeTime,
+            Self::NextWeather,
+            Self::NextGameMode,
+        ]
+        .into_iter()
+        .chain((0..HOTBAR_SIZE).map(Self::Hotbar))
+    }
+
+    /// The name used for the action in bindings files, such as `move_forward` or `hotbar_1`.
+    pub fn name(self) -> String {
+        let name = match self {
+            Self::MoveForward => "move_forward",
+            Self::MoveBackward => "move_backward",
+            Self::MoveLeft => "move_left",
+            Self::MoveRight => "move_right",
+            Self::Jump => "jump",
+            Self::Sneak => "sneak",
+            Self::Sprint => "sprint",
+            Self::Break => "break",
+            Self::Place => "place",
+            Self::Crafting => "crafting",
+            Self::ReleaseCursor => "release_cursor",
+            Self::ToggleFlying => "toggle_flying",
+            Self::ToggleNoclip => "toggle_noclip",
+            Self::ToggleSpectator => "toggle_spectator",
+            Self::NextTimeOfDay => "next_time_of_day",
+            Self::FreezeTime => "freeze_time",
+            Self::NextWeather => "next_weather",
+            Self::NextGameMode => "next_game_mode",
+            Self::Hotbar(slot) => return format!("hotbar_{}", slot + 1),
+        };
+        name.to_string()
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().find(|action| action.name() == name)
+    }
+}
+
+/// A touch gesture that can be bound to an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    /// A short touch that doesn't move.
+    Tap,
+    /// A touch held in place for a while, which lasts until the finger is lifted.
+    LongPress,
+}
+
+/// A physical input that can be bound to an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Touch(Gesture),
+}
+
+/// Builds a table of keys and their names, which are the names of their `KeyCode` variants.
+macro_rules! key_names {
+    ($($key:ident),* $(,)?) => {
+        &[$((stringify!($key), KeyCode::$key)),*]
+    };
+}
+
+const KEYS: &[(&str, KeyCode)] = key_names![
+    KeyA,
+    KeyB,
+    KeyC,
+    KeyD,
+    KeyE,
+    KeyF,
+    KeyG,
+    KeyH,
+    KeyI,
+    KeyJ,
+    KeyK,
+    KeyL,
+    KeyM,
+    KeyN,
+    KeyO,
+    KeyP,
+    KeyQ,
+    KeyR,
+    KeyS,
+    KeyT,
+    KeyU,
+    KeyV,
+    KeyW,
+    KeyX,
+    KeyY,
+    KeyZ,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    Space,
+    Enter,
+    Tab,
+    Backspace,
+    Escape,
+    ShiftLeft,
+    ShiftRight,
+    ControlLeft,
+    ControlRight,
+    AltLeft,
+    AltRight,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+    Backquote,
+    Minus,
+    Equal,
+    BracketLeft,
+    BracketRight,
+    Backslash,
+    Semicolon,
+    Quote,
+    Comma,
+    Period,
+    Slash,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+];
+
+const OTHER_INPUTS: [(&str, Input); 5] = [
+    ("MouseLeft", Input::Mouse(MouseButton::Left)),
+    ("MouseRight", Input::Mouse(MouseButton::Right)),
+    ("MouseMiddle", Input::Mouse(MouseButton::Middle)),
+    ("TouchTap", Input::Touch(Gesture::Tap)),
+    ("TouchLongPress", Input::Touch(Gesture::LongPress)),
+];
+
+impl Input {
+    fn all() -> impl Iterator<Item = (&'static str, Self)> {
+        KEYS.iter()
+            .map(|&(name, key)| (name, Self::Key(key)))
+            .chain(OTHER_INPUTS)
+    }
+
+    /// The name used for the input in bindings files, such as `KeyW` or `MouseLeft`. `None` for
+    /// keys and buttons that can't be bound.
+    pub fn name(self) -> Option<&'static str> {
+        Self::all()
+            .find(|&(_, input)| input == self)
+            .map(|(name, _)| name)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .find(|&(input_name, _)| input_name == name)
+            .map(|(_, input)| input)
+    }
+}
+
+/// Which inputs trigger which actions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bindings {
+    bindings: Vec<(Input, Action)>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        let (bindings, _) = Self::parse_without_defaults(DEFAULT_BINDINGS)
+            .expect("default bindings should be valid");
+        bindings
+    }
+}
+
+impl Bindings {
+    /// Bindings with nothing bound.
+    pub fn empty() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Parses a bindings file, in the format of `assets/bindings.txt`. Actions it doesn't
+    /// mention keep their default bindings.
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let (mut bindings, mentioned) = Self::parse_without_defaults(source)?;
+        for &(input, action) in &Self::default().bindings {
+            if !mentioned.contains(&action) && bindings.action(input).is_none() {
+                bindings.bind(input, action);
+            }
+        }
+        Ok(bindings)
+    }
+
+    /// Parses a bindings file into just the bindings it lists, along with the actions it
+    /// mentions.
+    fn parse_without_defaults(source: &str) -> Result<(Self, Vec<Action>), ParseError> {
+        let mut bindings = Self::empty();
+        let mut mentioned = Vec::new();
+        for (i, line) in source.lines().enumerate() {
+            let error = |message: String| ParseError::new(i + 1, message);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (action, inputs) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected `action = inputs`".to_string()))?;
+            let action = action.trim();
+            let action = Action::from_name(action)
+                .ok_or_else(|| error(format!("unknown action `{action}`")))?;
+            mentioned.push(action);
+            for name in inputs.split_whitespace() {
+                let input = Input::from_name(name)
+                    .ok_or_else(|| error(format!("unknown input `{name}`")))?;
+                bindings.bind(input, action);
+            }
+        }
+        Ok((bindings, mentioned))
+    }
+
+    /// Binds `input` to `action`, replacing whatever it was bound to before.
+    pub fn bind(&mut self, input: Input, action: Action) {
+        self.bindings.retain(|&(bound, _)| bound != input);
+        self.bindings.push((input, action));
+    }
+
+    pub fn unbind(&mut self, input: Input) {
+        self.bindings.retain(|&(bound, _)| bound != input);
+    }
+
+    /// The action `input` is bound to, if any.
+    pub fn action(&self, input: Input) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|&&(bound, _)| bound == input)
+            .map(|&(_, action)| action)
+    }
+
+    /// Every input bound to `action`.
+    pub fn inputs(&self, action: Action) -> impl Iterator<Item = Input> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |&&(_, bound)| bound == action)
+            .map(|&(input, _)| input)
+    }
+}
+
+/// Writes the bindings in the format of a bindings file.
+impl fmt::Display for Bindings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for action in Action::all() {
+            write!(f, "{} =", action.name())?;
+            for name in self.inputs(action).filter_map(Input::name) {
+                write!(f, " {name}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Keeps track of which inputs are held down, so that actions can be looked up by whether they
+/// are held rather than by which key was pressed last.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Controls {
+    pub bindings: Bindings,
+    held: Vec<Input>,
+}
+
+impl Controls {
+    pub fn new(bindings: Bindings) -> Self {
+        Self {
+            bindings,
+            held: Vec::new(),
+        }
+    }
+
+    /// Records `input` being pressed or released. Returns the action it is bound to, if any.
+    pub fn handle(&mut self, input: Input, pressed: bool) -> Option<Action> {
+        self.held.retain(|&held| held != input);
+        if pressed {
+            self.held.push(input);
+        }
+        self.bindings.action(input)
+    }
+
+    /// Whether any input bound to `action` is held down.
+    pub fn is_held(&self, action: Action) -> bool {
+        self.held
+            .iter()
+            .any(|&input| self.bindings.action(input) == Some(action))
+    }
+}
+
+/// Something that happened to the touch being followed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GestureEvent {
+    /// The finger moved by this many pixels.
+    Drag(Vec2),
+    Pressed(Gesture),
+    Released(Gesture),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TrackedTouch {
+    id: u64,
+    start: PhysicalPosition<f64>,
+    last: PhysicalPosition<f64>,
+    /// Seconds since the finger went down.
+    held_time: f32,
+    /// Whether it has moved too far to be a tap or long press.
+    moved: bool,
+    long_press: bool,
+}
+
+/// Turns raw touch events into drags, taps and long presses. Only the first finger down is
+/// followed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Gestures {
+    touch: Option<TrackedTouch>,
+}
+
+impl Gestures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn touch(&mut self, touch: &Touch) -> Vec<GestureEvent> {
+        if touch.phase == TouchPhase::Started && self.touch.is_none() {
+            self.touch = Some(TrackedTouch {
+                id: touch.id,
+                start: touch.location,
+                last: touch.location,
+                held_time: 0.0,
+                moved: false,
+                long_press: false,
+            });
+            return Vec::new();
+        }
+        let Some(tracked) = self.touch.as_mut().filter(|t| t.id == touch.id) else {
+            return Vec::new();
+        };
+
+        match touch.phase {
+            TouchPhase::Started => Vec::new(),
+            TouchPhase::Moved => {
+                let delta = vec2(
+                    (touch.location.x - tracked.last.x) as f32,
+                    (touch.location.y - tracked.last.y) as f32,
+                );
+                tracked.last = touch.location;
+                let distance =
+                    (touch.location.x - tracked.start.x).hypot(touch.location.y - tracked.start.y);
+                tracked.moved |= distance > TOUCH_SLOP;
+                vec![GestureEvent::Drag(delta)]
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                let tracked = self.touch.take().unwrap();
+                if tracked.long_press {
+                    vec![GestureEvent::Released(Gesture::LongPress)]
+                } else if !tracked.moved && touch.phase == TouchPhase::Ended {
+                    vec![
+                        GestureEvent::Pressed(Gesture::Tap),
+                        GestureEvent::Released(Gesture::Tap),
+                    ]
+                } else {
+                    Vec::new()
+                }
+            }
+        }
+    }
+
+    /// Advances time by `delta_time` seconds, returning a long press if one has just started.
+    pub fn update(&mut self, delta_time: f32) -> Option<GestureEvent> {
+        let tracked = self.touch.as_mut()?;
+        tracked.held_time += delta_time;
+        if tracked.moved || tracked.long_press || tracked.held_time < LONG_PRESS_TIME {
+            return None;
+        }
+        tracked.long_press = true;
+        Some(GestureEvent::Pressed(Gesture::LongPress))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::{event::MouseButton, keyboard::KeyCode};
+
+    use super::{Action, Bindings, Controls, Input};
+
+    #[test]
+    fn default_bindings_round_trip() {
+        let bindings = Bindings::default();
+        assert_eq!(
+            bindings.action(Input::Key(KeyCode::KeyA)),
+            Some(Action::MoveLeft)
+        );
+        assert_eq!(
+            bindings.action(Input::Key(KeyCode::Digit3)),
+            Some(Action::Hotbar(2))
+        );
+
+        assert_eq!(Bindings::parse(&bindings.to_string()), Ok(bindings));
+    }
+
+    #[test]
+    fn files_only_change_what_they_mention() {
+        let bindings = Bindings::parse("jump = KeyJ MouseMiddle\nsneak =\n").unwrap();
+
+        assert_eq!(
+            bindings.action(Input::Key(KeyCode::KeyJ)),
+            Some(Action::Jump)
+        );
+        assert_eq!(bindings.action(Input::Key(KeyCode::Space)), None);
+        assert_eq!(bindings.action(Input::Key(KeyCode::ShiftLeft)), None);
+        assert_eq!(
+            bindings.action(Input::Mouse(MouseButton::Left)),
+            Some(Action::Break)
+        );
+        assert!(Bindings::parse("jump = Spacebar").is_err());
+    }
+
+    #[test]
+    fn repeated_lines_leave_other_actions_bound() {
+        let bindings = Bindings::parse(&"jump = Space\n".repeat(100)).unwrap();
+
+        assert_eq!(
+            bindings.action(Input::Key(KeyCode::KeyW)),
+            Some(Action::MoveForward)
+        );
+    }
+
+    #[test]
+    fn actions_stay_held_while_any_input_is() {
+        let mut bindings = Bindings::empty();
+        bindings.bind(Input::Key(KeyCode::KeyW), Action::MoveForward);
+        bindings.bind(Input::Key(KeyCode::ArrowUp), Action::MoveForward);
+        let mut controls = Controls::new(bindings);
+
+        controls.handle(Input::Key(KeyCode::KeyW), true);
+        controls.handle(Input::Key(KeyCode::ArrowUp), true);
+        controls.handle(Input::Key(KeyCode::KeyW), false);
+        assert!(controls.is_held(Action::MoveForward));
+
+        controls.handle(Input::Key(KeyCode::ArrowUp), false);
+        assert!(!controls.is_held(Action::MoveForward));
+    }
+}
@@ -0,0 +1,26 @@
+use blocks_game::item::{Item, ToolTier};
+
+pub fn tier_color(tier: ToolTier) -> [f32; 4] {
+    match tier {
+        ToolTier::Wood => [0.6, 0.45, 0.25, 1.0],
+        ToolTier::Stone => [0.5, 0.5, 0.5, 1.0],
+        ToolTier::Iron => [0.85, 0.85, 0.85, 1.0],
+        ToolTier::Diamond => [0.4, 0.9, 0.9, 1.0],
+    }
+}
+
+/// A flat colour standing in for an item's icon.
+pub fn item_color(item: Item) -> [f32; 4] {
+    match item {
+        Item::Block(block) => {
+            let [r, g, b] = block.color().map(|c| c as f32 / 255.0);
+            [r, g, b, 1.0]
+        }
+        Item::Apple => [0.85, 0.1, 0.1, 1.0],
+        Item::Bread => [0.8, 0.6, 0.3, 1.0],
+        Item::RawMeat => [0.9, 0.45, 0.45, 1.0],
+        Item::CookedMeat => [0.55, 0.3, 0.2, 1.0],
+        Item::Bone => [0.9, 0.9, 0.8, 1.0],
+        Item::Tool(_, tier) => tier_color(tier),
+    }
+}
@@ -0,0 +1,63 @@
+// Vertex shader
+
+const SIZE: f32 = 0.25;
+
+struct Camera {
+    matrix: mat4x4<f32>,
+    position: vec3<f32>,
+    _aspect: f32,
+    sun_direction: vec3<f32>,
+    brightness: f32,
+    sky_color: vec3<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+};
+
+struct InstanceInput {
+    @location(2) position: vec3<f32>,
+    @location(3) angle: f32,
+    @location(4) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) relative_position: vec3<f32>,
+};
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+    instance: InstanceInput,
+) -> VertexOutput {
+    let rotation = mat3x3<f32>(
+        vec3<f32>(cos(instance.angle), 0.0, -sin(instance.angle)),
+        vec3<f32>(0.0, 1.0, 0.0),
+        vec3<f32>(sin(instance.angle), 0.0, cos(instance.angle)),
+    );
+    let center = instance.position + vec3<f32>(0.0, SIZE / 2.0, 0.0);
+    let world_position = center + rotation * (model.position * SIZE);
+
+    var out: VertexOutput;
+    out.clip_position = camera.matrix * vec4(world_position, 1.0);
+    out.relative_position = world_position - camera.position;
+
+    let sunlight = max(0.0, dot(rotation * model.normal, camera.sun_direction));
+    let light_intensity = camera.brightness * (0.5 + 0.5 * sunlight);
+    out.color = vec4(instance.color.rgb * light_intensity, instance.color.a);
+    return out;
+}
+
+// Fragment shader
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let too_far = clamp((length(in.relative_position) - 40.0) / 8.0, 0.0, 1.0);
+    return in.color + too_far * (vec4(camera.sky_color, 1.0) - in.color);
+}
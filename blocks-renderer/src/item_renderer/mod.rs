@@ -0,0 +1,204 @@
+use std::mem;
+
+use glam::Vec3;
+use wgpu::util::DeviceExt;
+
+use blocks_game::entity::{item::bob_offset, Entities};
+
+use crate::{item_colors::item_color, texture};
+
+/// Radians per second that dropped items spin at.
+const SPIN_SPEED: f32 = 1.0;
+const MAX_ITEMS: usize = 1024;
+
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+pub(crate) struct Vertex {
+    position: Vec3,
+    normal: Vec3,
+}
+
+impl Vertex {
+    const ATTRIBS: [wgpu::VertexAttribute; 2] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3];
+
+    pub(crate) fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct Instance {
+    position: Vec3,
+    angle: f32,
+    color: [f32; 4],
+}
+
+impl Instance {
+    const ATTRIBS: [wgpu::VertexAttribute; 3] =
+        wgpu::vertex_attr_array![2 => Float32x3, 3 => Float32, 4 => Float32x4];
+
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+/// Draws dropped items as small spinning cubes.
+pub struct ItemRenderer {
+    render_pipeline: wgpu::RenderPipeline,
+    vertex_buffer: wgpu::Buffer,
+    instance_buffer: wgpu::Buffer,
+    instance_count: u32,
+}
+
+impl ItemRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        color_target_format: wgpu::TextureFormat,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("item_shader.wgsl"));
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Item Render Pipeline Layout"),
+                bind_group_layouts: &[camera_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Item Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc(), Instance::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: color_target_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: texture::Texture::DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Item Vertex Buffer"),
+            contents: bytemuck::cast_slice(&cube_vertices()),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Item Instance Buffer"),
+            size: (MAX_ITEMS * mem::size_of::<Instance>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            render_pipeline,
+            vertex_buffer,
+            instance_buffer,
+            instance_count: 0,
+        }
+    }
+
+    /// Uploads the dropped items to draw, `interpolation` of the way between the previous and
+    /// latest ticks.
+    pub fn update(&mut self, queue: &wgpu::Queue, entities: &Entities, interpolation: f32) {
+        let instances: Vec<_> = entities
+            .iter()
+            .filter_map(|(_, entity)| Some((entity, entity.as_item()?)))
+            .take(MAX_ITEMS)
+            .map(|(entity, item)| Instance {
+                position: entity.interpolated_position(interpolation)
+                    + bob_offset(entity.age) * Vec3::Y,
+                angle: entity.age * SPIN_SPEED,
+                color: item_color(item.stack.item),
+            })
+            .collect();
+
+        queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
+        self.instance_count = instances.len() as u32;
+    }
+
+    pub fn render(&self, render_pass: &mut wgpu::RenderPass, camera_bind_group: &wgpu::BindGroup) {
+        if self.instance_count == 0 {
+            return;
+        }
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, camera_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        render_pass.draw(0..36, 0..self.instance_count);
+    }
+}
+
+/// A unit cube centred on the origin, wound counter-clockwise when seen from outside.
+pub(crate) fn cube_vertices() -> Vec<Vertex> {
+    let mut vertices = Vec::with_capacity(36);
+    for axis in 0..3 {
+        for side in [-0.5, 0.5] {
+            let mut normal = Vec3::ZERO;
+            normal[axis] = side * 2.0;
+            let corner = |u: f32, v: f32| {
+                let mut position = normal / 2.0;
+                position[(axis + 1) % 3] = u;
+                position[(axis + 2) % 3] = v;
+                Vertex { position, normal }
+            };
+
+            let mut face = [
+                corner(-0.5, -0.5),
+                corner(0.5, -0.5),
+                corner(0.5, 0.5),
+                corner(-0.5, -0.5),
+                corner(0.5, 0.5),
+                corner(-0.5, 0.5),
+            ];
+            if side < 0.0 {
+                face.reverse();
+            }
+            vertices.extend(face);
+        }
+    }
+    vertices
+}
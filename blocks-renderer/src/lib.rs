@@ -1,32 +1,46 @@
 use clock::Clock;
+use glam::{vec2, vec3, Vec2};
+use input::{Action, Bindings, Controls, Gesture, GestureEvent, Gestures, Input};
+use touch_controls::{TouchControls, TouchResponse};
 use wgpu::util::DeviceExt;
 use winit::{
     dpi::PhysicalPosition,
     error::EventLoopError,
-    event::{DeviceEvent, ElementState, Event, KeyEvent, MouseButton, TouchPhase, WindowEvent},
+    event::{DeviceEvent, ElementState, Event, MouseScrollDelta, WindowEvent},
     event_loop::EventLoop,
-    keyboard::{KeyCode, PhysicalKey},
+    keyboard::PhysicalKey,
     window::{CursorGrabMode, Window},
 };
 
-use blocks_game::{bounding_box::BoundingBox, terrain::block::Block, Game};
+use blocks_game::{
+    crafting::Click,
+    player::MovementMode,
+    save::SaveData,
+    time::{MIDNIGHT, NOON, SUNRISE, SUNSET},
+    weather::WeatherKind,
+    Game,
+};
 
 pub mod clock;
+pub mod input;
 
 mod camera;
+mod crack_renderer;
 mod hud_renderer;
+mod item_colors;
+mod item_renderer;
+mod mob_renderer;
 mod ray_casting;
 mod texture;
+mod touch_controls;
 mod voxel_renderer;
-
-const CORNFLOWER_BLUE: wgpu::Color = wgpu::Color {
-    r: 0.4,
-    g: 0.6,
-    b: 0.9,
-    a: 1.0,
-};
+mod weather_renderer;
 
 const MOUSE_SENSITIVITY: f32 = 0.1;
+/// Seconds that weather chosen with the weather key lasts.
+const CHOSEN_WEATHER_TIME: f32 = 600.0;
+
+type SaveHandler<'a> = Box<dyn FnMut(&SaveData) + 'a>;
 
 pub struct State<'a, C: Clock> {
     surface: wgpu::Surface<'a>,
@@ -41,12 +55,24 @@ pub struct State<'a, C: Clock> {
     camera_bind_group: wgpu::BindGroup,
     depth_texture: texture::Texture,
     voxel_renderer: voxel_renderer::VoxelRenderer,
+    crack_renderer: crack_renderer::CrackRenderer,
+    item_renderer: item_renderer::ItemRenderer,
+    mob_renderer: mob_renderer::MobRenderer,
+    weather_renderer: weather_renderer::WeatherRenderer,
     hud_renderer: hud_renderer::HudRenderer,
     game: Game,
     clock: C,
     last_frame: C::Instant,
     cursor_grabbed: bool,
-    last_touch_location: PhysicalPosition<f64>,
+    /// Whether the break button is being held down.
+    breaking: bool,
+    controls: Controls,
+    gestures: Gestures,
+    touch_controls: TouchControls,
+    /// Where the mouse is, in HUD coordinates.
+    cursor_position: Vec2,
+    /// Called with the world's settings whenever they change.
+    save_handler: Option<SaveHandler<'a>>,
 }
 
 impl<'a, C: Clock> State<'a, C> {
@@ -113,7 +139,7 @@ impl<'a, C: Clock> State<'a, C> {
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 entries: &[wgpu::BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
@@ -143,6 +169,19 @@ impl<'a, C: Clock> State<'a, C> {
             config.format,
         );
 
+        let crack_renderer =
+            crack_renderer::CrackRenderer::new(&device, &camera_bind_group_layout, config.format);
+
+        let item_renderer =
+            item_renderer::ItemRenderer::new(&device, &camera_bind_group_layout, config.format);
+        let mob_renderer =
+            mob_renderer::MobRenderer::new(&device, &camera_bind_group_layout, config.format);
+        let weather_renderer = weather_renderer::WeatherRenderer::new(
+            &device,
+            &camera_bind_group_layout,
+            config.format,
+        );
+
         let hud_renderer = hud_renderer::HudRenderer::new(
             &device,
             &queue,
@@ -165,12 +204,21 @@ impl<'a, C: Clock> State<'a, C> {
             camera_bind_group,
             depth_texture,
             voxel_renderer,
+            crack_renderer,
+            item_renderer,
+            mob_renderer,
+            weather_renderer,
             hud_renderer,
             game,
             last_frame: clock.now(),
             clock,
             cursor_grabbed: false,
-            last_touch_location: PhysicalPosition::new(0.0, 0.0),
+            breaking: false,
+            controls: Controls::new(Bindings::default()),
+            gestures: Gestures::new(),
+            touch_controls: TouchControls::new(),
+            cursor_position: Vec2::ZERO,
+            save_handler: None,
         }
     }
 
@@ -182,39 +230,31 @@ impl<'a, C: Clock> State<'a, C> {
             Event::WindowEvent {
                 ref event,
                 window_id,
-            } if window_id == self.window.id() => {
-                if !self.input(event) {
-                    match event {
-                        WindowEvent::CloseRequested => control_flow.exit(),
-                        WindowEvent::Resized(physical_size) => {
-                            if !self.manual_size {
-                                self.resize(*physical_size);
-                            }
+            } if window_id == self.window.id() && !self.input(event) => match event {
+                WindowEvent::CloseRequested => control_flow.exit(),
+                WindowEvent::Resized(physical_size) if !self.manual_size => {
+                    self.resize(*physical_size);
+                }
+                WindowEvent::RedrawRequested => {
+                    self.window.request_redraw();
+
+                    self.update();
+                    match self.render() {
+                        Ok(_) => {}
+                        Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                            self.resize(self.size);
+                        }
+                        Err(wgpu::SurfaceError::OutOfMemory | wgpu::SurfaceError::Other) => {
+                            log::error!("OutOfMemory");
+                            control_flow.exit();
                         }
-                        WindowEvent::RedrawRequested => {
-                            self.window.request_redraw();
-
-                            self.update();
-                            match self.render() {
-                                Ok(_) => {}
-                                Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
-                                    self.resize(self.size);
-                                }
-                                Err(
-                                    wgpu::SurfaceError::OutOfMemory | wgpu::SurfaceError::Other,
-                                ) => {
-                                    log::error!("OutOfMemory");
-                                    control_flow.exit();
-                                }
-                                Err(wgpu::SurfaceError::Timeout) => {
-                                    log::warn!("Surface timeout")
-                                }
-                            }
+                        Err(wgpu::SurfaceError::Timeout) => {
+                            log::warn!("Surface timeout")
                         }
-                        _ => {}
                     }
                 }
-            }
+                _ => {}
+            },
             _ => {}
         })
     }
@@ -237,122 +277,248 @@ impl<'a, C: Clock> State<'a, C> {
 
     fn input(&mut self, event: &WindowEvent) -> bool {
         match event {
-            WindowEvent::MouseInput {
-                button: MouseButton::Left,
-                state: ElementState::Pressed,
-                ..
-            } => {
-                if self.cursor_grabbed {
-                    if let Some((block_pos, _)) = ray_casting::ray_cast(
-                        self.game.player.head_position(),
-                        self.game.player.looking_direction(),
-                        &self.game.terrain,
-                    ) {
-                        self.game.terrain.set_block(block_pos, Block::AIR);
-                    }
-                } else {
-                    self.window.set_cursor_grab(CursorGrabMode::Locked).unwrap();
-                    self.window.set_cursor_visible(false);
-                    self.cursor_grabbed = true;
+            WindowEvent::MouseInput { button, state, .. } => self.handle_input(
+                Input::Mouse(*button),
+                *state == ElementState::Pressed,
+                false,
+            ),
+            WindowEvent::KeyboardInput { event, .. } => match event.physical_key {
+                PhysicalKey::Code(code) => {
+                    // Someone typing isn't using the touch screen.
+                    self.touch_controls.active = false;
+                    self.handle_input(
+                        Input::Key(code),
+                        event.state == ElementState::Pressed,
+                        event.repeat,
+                    )
+                }
+                PhysicalKey::Unidentified(_) => false,
+            },
+            WindowEvent::MouseWheel { delta, .. } => {
+                let y = match *delta {
+                    MouseScrollDelta::LineDelta(_, y) => y,
+                    MouseScrollDelta::PixelDelta(position) => position.y as f32,
+                };
+                // Scrolling down moves the selection to the right.
+                if y != 0.0 {
+                    self.game.player.inventory.scroll(-y.signum() as i32);
                 }
                 true
             }
-            WindowEvent::MouseInput {
-                button: MouseButton::Right,
-                state: ElementState::Pressed,
-                ..
-            } => {
-                if let Some((block_pos, face)) = ray_casting::ray_cast(
-                    self.game.player.head_position(),
-                    self.game.player.looking_direction(),
-                    &self.game.terrain,
-                ) {
-                    let new_block_pos = block_pos + face;
-                    if !BoundingBox::of_block(new_block_pos)
-                        .intersects(&self.game.player.bounding_box())
-                    {
-                        self.game.terrain.set_block(new_block_pos, Block::STONE);
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor_position = self.hud_position(*position);
+                false
+            }
+            WindowEvent::Touch(touch) => {
+                let response = self.touch_controls.touch(
+                    touch.id,
+                    touch.phase,
+                    self.hud_position(touch.location),
+                    self.camera.aspect,
+                );
+                self.touch_controls.active = true;
+                match response {
+                    TouchResponse::Ignored => {
+                        for gesture in self.gestures.touch(touch) {
+                            self.handle_gesture(gesture);
+                        }
+                    }
+                    TouchResponse::Moved | TouchResponse::JumpReleased => self.update_movement(),
+                    TouchResponse::JumpPressed => {
+                        self.update_movement();
+                        self.press_action(Action::Jump, Input::Touch(Gesture::Tap));
                     }
                 }
                 true
             }
-            WindowEvent::KeyboardInput {
-                event:
-                    KeyEvent {
-                        state: ElementState::Pressed,
-                        physical_key: PhysicalKey::Code(KeyCode::Escape),
-                        ..
-                    },
-                ..
-            } => {
-                self.window.set_cursor_grab(CursorGrabMode::None).unwrap();
-                self.window.set_cursor_visible(true);
-                self.cursor_grabbed = false;
-                true
+            _ => false,
+        }
+    }
+
+    /// Converts a position on the window to HUD coordinates.
+    fn hud_position(&self, position: PhysicalPosition<f64>) -> Vec2 {
+        vec2(
+            (2.0 * position.x as f32 / self.size.width as f32 - 1.0) * self.camera.aspect,
+            1.0 - 2.0 * position.y as f32 / self.size.height as f32,
+        )
+    }
+
+    fn handle_gesture(&mut self, gesture: GestureEvent) {
+        match gesture {
+            GestureEvent::Drag(delta) => {
+                self.game
+                    .player
+                    .turn_head(MOUSE_SENSITIVITY * vec2(delta.y, delta.x));
             }
-            WindowEvent::KeyboardInput { event, .. } => match event.physical_key {
-                PhysicalKey::Code(KeyCode::KeyW) => {
-                    match event.state {
-                        ElementState::Pressed => self.game.player.walk_vector.z = 1.0,
-                        ElementState::Released => self.game.player.walk_vector.z = 0.0,
-                    }
-                    true
-                }
-                PhysicalKey::Code(KeyCode::KeyA) => {
-                    match event.state {
-                        ElementState::Pressed => self.game.player.walk_vector.x = 1.0,
-                        ElementState::Released => self.game.player.walk_vector.x = 0.0,
-                    }
-                    true
+            GestureEvent::Pressed(gesture) => {
+                self.handle_input(Input::Touch(gesture), true, false);
+            }
+            GestureEvent::Released(gesture) => {
+                self.handle_input(Input::Touch(gesture), false, false);
+            }
+        }
+    }
+
+    /// Performs whatever `input` is bound to. Returns whether it was bound to anything.
+    fn handle_input(&mut self, input: Input, pressed: bool, repeat: bool) -> bool {
+        let Some(action) = self.controls.handle(input, pressed) else {
+            return false;
+        };
+        self.update_movement();
+        if pressed && !repeat {
+            self.press_action(action, input);
+        } else if !pressed && action == Action::Break {
+            self.breaking = false;
+        }
+        true
+    }
+
+    /// Sets how the player is trying to move from the movement actions being held.
+    fn update_movement(&mut self) {
+        let held = |action| self.controls.is_held(action) as i32 as f32;
+        let player = &mut self.game.player;
+        if player.is_dead() {
+            return;
+        }
+        let joystick = self.touch_controls.walk_direction();
+        let jumping = self.controls.is_held(Action::Jump) || self.touch_controls.jump_held();
+        // Walking left is towards +x.
+        player.walk_vector = vec3(
+            (held(Action::MoveLeft) - held(Action::MoveRight) - joystick.x).clamp(-1.0, 1.0),
+            jumping as i32 as f32 - held(Action::Sneak),
+            (held(Action::MoveForward) - held(Action::MoveBackward) + joystick.y).clamp(-1.0, 1.0),
+        );
+        player.sneaking = self.controls.is_held(Action::Sneak);
+        player.sprinting = self.controls.is_held(Action::Sprint);
+    }
+
+    /// Starts `action`, which was triggered by `input`.
+    fn press_action(&mut self, action: Action, input: Input) {
+        let touch = matches!(input, Input::Touch(_));
+        match action {
+            Action::Jump => {
+                if self.game.player.is_dead() {
+                    self.game.player.respawn();
+                } else {
+                    self.game.player.jump();
                 }
-                PhysicalKey::Code(KeyCode::KeyS) => {
-                    match event.state {
-                        ElementState::Pressed => self.game.player.walk_vector.z = -1.0,
-                        ElementState::Released => self.game.player.walk_vector.z = 0.0,
-                    }
-                    true
+            }
+            Action::Break if self.game.crafting.is_some() => {
+                self.click_crafting_slot(Click::Stack);
+            }
+            Action::Break if self.cursor_grabbed || touch => {
+                let mob = ray_casting::ray_cast_mob(
+                    self.game.player.head_position(),
+                    self.game.player.looking_direction(),
+                    &self.game.entities,
+                    &self.game.terrain,
+                );
+                if !mob.is_some_and(|id| self.game.attack(id)) {
+                    self.breaking = true;
                 }
-                PhysicalKey::Code(KeyCode::KeyD) => {
-                    match event.state {
-                        ElementState::Pressed => self.game.player.walk_vector.x = -1.0,
-                        ElementState::Released => self.game.player.walk_vector.x = 0.0,
-                    }
-                    true
+            }
+            Action::Break => self.grab_cursor(),
+            Action::Place if self.game.crafting.is_some() => {
+                self.click_crafting_slot(Click::Single);
+            }
+            Action::Place => {
+                if !self.game.player.can_interact() || self.game.player.eat_selected() {
+                    return;
                 }
-                PhysicalKey::Code(KeyCode::Space) => match event.state {
-                    ElementState::Pressed => {
-                        self.game.player.jump();
-                        true
-                    }
-                    _ => false,
-                },
-                PhysicalKey::Code(KeyCode::ControlLeft) => {
-                    match event.state {
-                        ElementState::Pressed => self.game.player.sprinting = true,
-                        ElementState::Released => self.game.player.sprinting = false,
-                    }
-                    true
+                if let Some((block_pos, face)) = ray_casting::ray_cast(
+                    self.game.player.head_position(),
+                    self.game.player.looking_direction(),
+                    &self.game.terrain,
+                ) {
+                    let position = if self.game.terrain.block(block_pos).is_replaceable() {
+                        block_pos
+                    } else {
+                        block_pos + face
+                    };
+                    self.game.place_block(position, face);
                 }
-                _ => false,
-            },
-            WindowEvent::Touch(touch) => match touch.phase {
-                TouchPhase::Started => {
-                    self.last_touch_location = touch.location;
-                    true
+            }
+            Action::Crafting => {
+                if self.game.crafting.is_some() {
+                    self.game.close_crafting();
+                    self.grab_cursor();
+                } else {
+                    self.game.open_crafting();
+                    self.release_cursor();
                 }
-                TouchPhase::Moved => {
-                    let delta_x = touch.location.x - self.last_touch_location.x;
-                    let delta_y = touch.location.y - self.last_touch_location.y;
+            }
+            Action::ReleaseCursor => {
+                self.game.close_crafting();
+                self.release_cursor();
+            }
+            Action::ToggleFlying => self.game.toggle_movement_mode(MovementMode::Flying),
+            Action::ToggleNoclip => self.game.toggle_movement_mode(MovementMode::Noclip),
+            Action::ToggleSpectator => self.game.toggle_movement_mode(MovementMode::Spectator),
+            Action::NextTimeOfDay => {
+                let time_of_day = next_time_of_day(self.game.time.time_of_day());
+                self.game.time.set_time_of_day(time_of_day);
+            }
+            Action::FreezeTime => self.game.time.frozen = !self.game.time.frozen,
+            Action::NextWeather => {
+                let kind = next_weather(self.game.weather.kind);
+                self.game.weather.set(kind, CHOSEN_WEATHER_TIME);
+            }
+            Action::NextGameMode => {
+                let mode = self.game.game_mode().next();
+                self.game.set_game_mode(mode);
+                self.save();
+            }
+            Action::Hotbar(slot) => self.game.player.inventory.select(slot),
+            Action::MoveForward
+            | Action::MoveBackward
+            | Action::MoveLeft
+            | Action::MoveRight
+            | Action::Sneak
+            | Action::Sprint => {}
+        }
+    }
 
-                    self.game.player.head_angle.x += MOUSE_SENSITIVITY * delta_y as f32;
-                    self.game.player.head_angle.y += MOUSE_SENSITIVITY * delta_x as f32;
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
 
-                    self.last_touch_location = touch.location;
-                    true
-                }
-                _ => false,
-            },
-            _ => false,
+    pub fn game_mut(&mut self) -> &mut Game {
+        &mut self.game
+    }
+
+    /// Sets what to do with the world's settings when they change, such as writing them to a
+    /// file.
+    pub fn set_save_handler(&mut self, handler: impl FnMut(&SaveData) + 'a) {
+        self.save_handler = Some(Box::new(handler));
+    }
+
+    fn save(&mut self) {
+        if let Some(handler) = &mut self.save_handler {
+            handler(&self.game.save());
+        }
+    }
+
+    /// Replaces the controls, such as with ones loaded from a bindings file.
+    pub fn set_bindings(&mut self, bindings: Bindings) {
+        self.controls = Controls::new(bindings);
+    }
+
+    fn grab_cursor(&mut self) {
+        self.window.set_cursor_grab(CursorGrabMode::Locked).unwrap();
+        self.window.set_cursor_visible(false);
+        self.cursor_grabbed = true;
+    }
+
+    fn release_cursor(&mut self) {
+        self.window.set_cursor_grab(CursorGrabMode::None).unwrap();
+        self.window.set_cursor_visible(true);
+        self.cursor_grabbed = false;
+        self.breaking = false;
+    }
+
+    fn click_crafting_slot(&mut self, click: Click) {
+        if let Some(slot) = hud_renderer::crafting::slot_at(self.cursor_position) {
+            self.game.click_crafting_slot(slot, click);
         }
     }
 
@@ -361,8 +527,9 @@ impl<'a, C: Clock> State<'a, C> {
             DeviceEvent::MouseMotion { delta } if self.cursor_grabbed => {
                 let &(delta_x, delta_y) = delta;
 
-                self.game.player.head_angle.x -= MOUSE_SENSITIVITY * delta_y as f32;
-                self.game.player.head_angle.y -= MOUSE_SENSITIVITY * delta_x as f32;
+                self.game
+                    .player
+                    .turn_head(-MOUSE_SENSITIVITY * vec2(delta_y as f32, delta_x as f32));
 
                 true
             }
@@ -374,9 +541,25 @@ impl<'a, C: Clock> State<'a, C> {
         let this_frame = self.clock.now();
         let delta_time = self.clock.seconds_elapsed(self.last_frame, this_frame);
 
+        if let Some(gesture) = self.gestures.update(delta_time) {
+            self.handle_gesture(gesture);
+        }
+
+        let target = if self.breaking && self.game.player.can_interact() {
+            ray_casting::ray_cast(
+                self.game.player.head_position(),
+                self.game.player.looking_direction(),
+                &self.game.terrain,
+            )
+            .map(|(block_pos, _)| block_pos)
+        } else {
+            None
+        };
+        self.game.mining.set_target(target);
+
         self.game.update(delta_time);
 
-        self.camera.update(&self.game.player);
+        self.camera.update(&self.game);
         self.queue.write_buffer(
             &self.camera_buffer,
             0,
@@ -384,6 +567,21 @@ impl<'a, C: Clock> State<'a, C> {
         );
 
         self.voxel_renderer.update(&self.device, &mut self.game);
+        self.crack_renderer.update(&self.queue, &self.game.mining);
+        let interpolation = self.game.interpolation();
+        self.item_renderer
+            .update(&self.queue, &self.game.entities, interpolation);
+        self.mob_renderer
+            .update(&self.queue, &self.game.entities, interpolation);
+        self.weather_renderer
+            .update(&self.queue, &self.game, delta_time);
+        self.hud_renderer.update(
+            &self.queue,
+            &self.game,
+            self.cursor_position,
+            &self.touch_controls,
+            self.camera.aspect,
+        );
 
         self.last_frame = this_frame;
     }
@@ -398,6 +596,7 @@ impl<'a, C: Clock> State<'a, C> {
         let view = output
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
+        let sky_color = self.game.sky_color();
 
         let mut encoder = self
             .device
@@ -412,7 +611,12 @@ impl<'a, C: Clock> State<'a, C> {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(CORNFLOWER_BLUE),
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: sky_color.x as f64,
+                            g: sky_color.y as f64,
+                            b: sky_color.z as f64,
+                            a: 1.0,
+                        }),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -431,6 +635,18 @@ impl<'a, C: Clock> State<'a, C> {
             self.voxel_renderer
                 .render(&mut render_pass, &self.camera_bind_group);
 
+            self.item_renderer
+                .render(&mut render_pass, &self.camera_bind_group);
+
+            self.mob_renderer
+                .render(&mut render_pass, &self.camera_bind_group);
+
+            self.crack_renderer
+                .render(&mut render_pass, &self.camera_bind_group);
+
+            self.weather_renderer
+                .render(&mut render_pass, &self.camera_bind_group);
+
             self.hud_renderer
                 .render(&mut render_pass, &self.camera_bind_group);
         }
@@ -441,3 +657,20 @@ impl<'a, C: Clock> State<'a, C> {
         Ok(())
     }
 }
+
+/// The next of sunrise, noon, sunset and midnight after `time_of_day`.
+fn next_time_of_day(time_of_day: f32) -> f32 {
+    [SUNRISE, NOON, SUNSET, MIDNIGHT]
+        .into_iter()
+        .find(|&t| t > time_of_day)
+        .unwrap_or(SUNRISE)
+}
+
+/// The weather after `kind` when cycling through clear skies, rain and thunderstorms.
+fn next_weather(kind: WeatherKind) -> WeatherKind {
+    match kind {
+        WeatherKind::Clear => WeatherKind::Rain,
+        WeatherKind::Rain => WeatherKind::Thunder,
+        WeatherKind::Thunder => WeatherKind::Clear,
+    }
+}
@@ -0,0 +1,169 @@
+use std::mem;
+
+use glam::Vec3;
+use wgpu::util::DeviceExt;
+
+use blocks_game::entity::{
+    mob::{MobKind, HURT_TIME},
+    Entities,
+};
+
+use crate::{
+    item_renderer::{cube_vertices, Vertex},
+    texture,
+};
+
+const MAX_MOBS: usize = 256;
+
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct Instance {
+    position: Vec3,
+    yaw: f32,
+    size: Vec3,
+    /// How red to tint the mob, from 0 to 1, after it has been hit.
+    hurt: f32,
+    color: [f32; 4],
+}
+
+impl Instance {
+    const ATTRIBS: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+        2 => Float32x3, 3 => Float32, 4 => Float32x3, 5 => Float32, 6 => Float32x4
+    ];
+
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+fn mob_color(kind: MobKind) -> [f32; 4] {
+    match kind {
+        MobKind::Pig => [0.95, 0.62, 0.66, 1.0],
+        MobKind::Zombie => [0.3, 0.55, 0.3, 1.0],
+    }
+}
+
+/// Draws mobs as boxes the size of their bounding boxes, with a darker face at the front.
+pub struct MobRenderer {
+    render_pipeline: wgpu::RenderPipeline,
+    vertex_buffer: wgpu::Buffer,
+    instance_buffer: wgpu::Buffer,
+    instance_count: u32,
+}
+
+impl MobRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        color_target_format: wgpu::TextureFormat,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("mob_shader.wgsl"));
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Mob Render Pipeline Layout"),
+                bind_group_layouts: &[camera_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Mob Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc(), Instance::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: color_target_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: texture::Texture::DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Mob Vertex Buffer"),
+            contents: bytemuck::cast_slice(&cube_vertices()),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Mob Instance Buffer"),
+            size: (MAX_MOBS * mem::size_of::<Instance>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            render_pipeline,
+            vertex_buffer,
+            instance_buffer,
+            instance_count: 0,
+        }
+    }
+
+    /// Uploads the mobs to draw, `interpolation` of the way between the previous and latest
+    /// ticks.
+    pub fn update(&mut self, queue: &wgpu::Queue, entities: &Entities, interpolation: f32) {
+        let instances: Vec<_> = entities
+            .iter()
+            .filter_map(|(_, entity)| Some((entity, entity.as_mob()?)))
+            .take(MAX_MOBS)
+            .map(|(entity, mob)| Instance {
+                position: entity.interpolated_position(interpolation),
+                yaw: mob.yaw,
+                size: Vec3::new(entity.body.width, entity.body.height, entity.body.width),
+                hurt: mob.hurt_time / HURT_TIME,
+                color: mob_color(mob.kind),
+            })
+            .collect();
+
+        queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
+        self.instance_count = instances.len() as u32;
+    }
+
+    pub fn render(&self, render_pass: &mut wgpu::RenderPass, camera_bind_group: &wgpu::BindGroup) {
+        if self.instance_count == 0 {
+            return;
+        }
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, camera_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        render_pass.draw(0..36, 0..self.instance_count);
+    }
+}
@@ -2,7 +2,8 @@ use glam::{IVec3, Vec3};
 
 use blocks_game::{
     bounding_box::BoundingBox,
-    terrain::{block::Block, Terrain},
+    entity::{Entities, EntityId},
+    terrain::Terrain,
     util::TotalOrd,
 };
 
@@ -19,25 +20,57 @@ pub fn ray_cast(origin: Vec3, direction: Vec3, terrain: &Terrain) -> Option<(IVe
 
     terrain
         .blocks_intersecting(bounding_box)
-        .filter(|&(_, b)| b != Block::AIR)
-        .filter_map(|(p, _)| intersect_block(origin, direction, p).and_then(|r| Some((p, r))))
-        .min_by_key(|&(_, r)| TotalOrd(r))
-        .filter(|&(p, _)| p.as_vec3().distance_squared(origin) <= MAX_REACH * MAX_REACH)
-        .map(|(p, r)| {
+        .filter(|&(_, b)| b.is_targetable())
+        .filter_map(|(p, b)| {
+            let bounds = b.shape(p);
+            intersect_box(origin, direction, bounds).map(|r| (p, bounds, r))
+        })
+        .min_by_key(|&(_, _, r)| TotalOrd(r))
+        .filter(|&(p, _, _)| p.as_vec3().distance_squared(origin) <= MAX_REACH * MAX_REACH)
+        .map(|(p, bounds, r)| {
+            // Scaled to a cube, so that thin blocks such as ladders pick the right face.
+            let center = (bounds.min + bounds.max) / 2.0;
+            let half_size = (bounds.max - bounds.min) / 2.0;
             (
                 p,
-                direction_of(origin + r * direction - (p.as_vec3() + Vec3::splat(0.5))),
+                direction_of((origin + r * direction - center) / half_size),
             )
         })
 }
 
-fn intersect_block(origin: Vec3, direction: Vec3, pos: IVec3) -> Option<f32> {
-    let (t_x_min, t_x_max) =
-        intersect_axis(origin.x, direction.x, pos.x as f32, pos.x as f32 + 1.0);
-    let (t_y_min, t_y_max) =
-        intersect_axis(origin.y, direction.y, pos.y as f32, pos.y as f32 + 1.0);
-    let (t_z_min, t_z_max) =
-        intersect_axis(origin.z, direction.z, pos.z as f32, pos.z as f32 + 1.0);
+/// Finds the mob that the player is clicking on, if it's closer than any block in the way.
+pub fn ray_cast_mob(
+    origin: Vec3,
+    direction: Vec3,
+    entities: &Entities,
+    terrain: &Terrain,
+) -> Option<EntityId> {
+    let reach = BoundingBox::new(
+        origin - Vec3::splat(MAX_REACH),
+        origin + Vec3::splat(MAX_REACH),
+    );
+    let (id, distance) = entities
+        .intersecting(reach)
+        .filter(|(_, entity)| entity.as_mob().is_some())
+        .filter_map(|(id, entity)| {
+            intersect_box(origin, direction, entity.body.bounding_box()).map(|r| (id, r))
+        })
+        .filter(|&(_, r)| r <= MAX_REACH)
+        .min_by_key(|&(_, r)| TotalOrd(r))?;
+
+    let block_distance = ray_cast(origin, direction, terrain)
+        .and_then(|(p, _)| intersect_box(origin, direction, terrain.block(p).shape(p)));
+    if block_distance.is_some_and(|b| b < distance) {
+        return None;
+    }
+    Some(id)
+}
+
+fn intersect_box(origin: Vec3, direction: Vec3, bounding_box: BoundingBox) -> Option<f32> {
+    let (min, max) = (bounding_box.min, bounding_box.max);
+    let (t_x_min, t_x_max) = intersect_axis(origin.x, direction.x, min.x, max.x);
+    let (t_y_min, t_y_max) = intersect_axis(origin.y, direction.y, min.y, max.y);
+    let (t_z_min, t_z_max) = intersect_axis(origin.z, direction.z, min.z, max.z);
 
     let t_min = t_x_min.max(t_y_min).max(t_z_min);
     let t_max = t_x_max.min(t_y_max).min(t_z_max);
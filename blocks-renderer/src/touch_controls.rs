@@ -0,0 +1,157 @@
+use glam::{vec2, Vec2};
+use winit::event::TouchPhase;
+
+/// Size of the joystick's base, in HUD units.
+pub const JOYSTICK_RADIUS: f32 = 0.2;
+/// How far from the middle of the joystick a touch can start and still grab it, as a multiple
+/// of its radius.
+const JOYSTICK_REACH: f32 = 1.5;
+pub const JUMP_RADIUS: f32 = 0.12;
+/// Gap between the controls and the sides of the screen.
+const EDGE_MARGIN: f32 = 0.1;
+/// Height of the middle of the controls, which is above the hotbar and status bars.
+const CONTROLS_Y: f32 = -0.35;
+
+/// What a touch did to the on-screen controls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchResponse {
+    /// The touch wasn't on a control, so it's for looking around and gestures.
+    Ignored,
+    /// The joystick moved, or a finger on the jump button moved.
+    Moved,
+    JumpPressed,
+    JumpReleased,
+}
+
+/// A virtual joystick for walking and a jump button, for playing on touch screens. Positions are
+/// in HUD coordinates.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TouchControls {
+    /// Whether the controls are shown and used, which they are once the screen has been touched.
+    pub active: bool,
+    /// The touch holding the joystick, and how far it has pushed the knob from the middle.
+    joystick: Option<(u64, Vec2)>,
+    /// The touch holding the jump button.
+    jump: Option<u64>,
+}
+
+impl TouchControls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The middle of the joystick, in the bottom left of a screen `aspect` wide.
+    pub fn joystick_center(aspect: f32) -> Vec2 {
+        vec2(-aspect + EDGE_MARGIN + JOYSTICK_RADIUS, CONTROLS_Y)
+    }
+
+    /// The middle of the jump button, in the bottom right of a screen `aspect` wide.
+    pub fn jump_center(aspect: f32) -> Vec2 {
+        vec2(aspect - EDGE_MARGIN - JOYSTICK_RADIUS, CONTROLS_Y)
+    }
+
+    /// How far the knob is pushed from the middle of the joystick.
+    pub fn knob_offset(&self) -> Vec2 {
+        self.joystick.map_or(Vec2::ZERO, |(_, offset)| offset)
+    }
+
+    /// Which way to walk, with up being forwards and a length of at most 1.
+    pub fn walk_direction(&self) -> Vec2 {
+        self.knob_offset() / JOYSTICK_RADIUS
+    }
+
+    pub fn jump_held(&self) -> bool {
+        self.jump.is_some()
+    }
+
+    /// Handles the touch `id` at `position` on a screen `aspect` wide.
+    pub fn touch(
+        &mut self,
+        id: u64,
+        phase: TouchPhase,
+        position: Vec2,
+        aspect: f32,
+    ) -> TouchResponse {
+        let joystick_center = Self::joystick_center(aspect);
+        let ended = matches!(phase, TouchPhase::Ended | TouchPhase::Cancelled);
+
+        if self.joystick.is_some_and(|(held_by, _)| held_by == id) {
+            self.joystick = (!ended).then(|| (id, clamp_knob(position - joystick_center)));
+            return TouchResponse::Moved;
+        }
+        if self.jump == Some(id) {
+            if ended {
+                self.jump = None;
+                return TouchResponse::JumpReleased;
+            }
+            return TouchResponse::Moved;
+        }
+        if phase != TouchPhase::Started || !self.active {
+            return TouchResponse::Ignored;
+        }
+
+        if self.joystick.is_none()
+            && position.distance(joystick_center) < JOYSTICK_RADIUS * JOYSTICK_REACH
+        {
+            self.joystick = Some((id, clamp_knob(position - joystick_center)));
+            TouchResponse::Moved
+        } else if self.jump.is_none() && position.distance(Self::jump_center(aspect)) < JUMP_RADIUS
+        {
+            self.jump = Some(id);
+            TouchResponse::JumpPressed
+        } else {
+            TouchResponse::Ignored
+        }
+    }
+}
+
+fn clamp_knob(offset: Vec2) -> Vec2 {
+    offset.clamp_length_max(JOYSTICK_RADIUS)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::vec2;
+    use winit::event::TouchPhase;
+
+    use super::{TouchControls, TouchResponse, JOYSTICK_RADIUS};
+
+    #[test]
+    fn joystick_and_jump_follow_their_own_fingers() {
+        let aspect = 1.5;
+        let joystick = TouchControls::joystick_center(aspect);
+        let jump = TouchControls::jump_center(aspect);
+        let mut controls = TouchControls::new();
+
+        // Touches aren't taken by the controls until they're shown.
+        assert_eq!(
+            controls.touch(0, TouchPhase::Started, joystick, aspect),
+            TouchResponse::Ignored
+        );
+        controls.active = true;
+
+        controls.touch(1, TouchPhase::Started, joystick, aspect);
+        assert_eq!(
+            controls.touch(2, TouchPhase::Started, jump, aspect),
+            TouchResponse::JumpPressed
+        );
+        assert_eq!(
+            controls.touch(3, TouchPhase::Started, vec2(0.0, 0.5), aspect),
+            TouchResponse::Ignored
+        );
+
+        // Pushing past the edge of the joystick walks at full speed.
+        controls.touch(1, TouchPhase::Moved, joystick + vec2(0.0, 1.0), aspect);
+        assert_eq!(controls.knob_offset(), vec2(0.0, JOYSTICK_RADIUS));
+        assert_eq!(controls.walk_direction(), vec2(0.0, 1.0));
+        assert!(controls.jump_held());
+
+        controls.touch(1, TouchPhase::Ended, joystick, aspect);
+        assert_eq!(
+            controls.touch(2, TouchPhase::Ended, jump, aspect),
+            TouchResponse::JumpReleased
+        );
+        assert_eq!(controls.walk_direction(), vec2(0.0, 0.0));
+        assert!(!controls.jump_held());
+    }
+}
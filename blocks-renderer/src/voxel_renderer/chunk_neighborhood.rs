@@ -24,12 +24,12 @@ pub struct SubchunkNeighborhood<'a> {
 impl SubchunkNeighborhood<'_> {
     pub fn is_dirty(&self) -> bool {
         self.center.dirty
-            || self.west.map_or(false, |c| c.dirty)
-            || self.east.map_or(false, |c| c.dirty)
-            || self.top.map_or(false, |c| c.dirty)
-            || self.bottom.map_or(false, |c| c.dirty)
-            || self.north.map_or(false, |c| c.dirty)
-            || self.south.map_or(false, |c| c.dirty)
+            || self.west.is_some_and(|c| c.dirty)
+            || self.east.is_some_and(|c| c.dirty)
+            || self.top.is_some_and(|c| c.dirty)
+            || self.bottom.is_some_and(|c| c.dirty)
+            || self.north.is_some_and(|c| c.dirty)
+            || self.south.is_some_and(|c| c.dirty)
     }
 
     pub fn block(&self, pos: IVec3) -> Block {
@@ -5,7 +5,10 @@ use glam::{ivec3, u8vec3, I8Vec3, IVec3, U8Vec3};
 use wgpu::util::DeviceExt;
 
 use blocks_game::{
-    terrain::{block::Block, subchunk::Subchunk},
+    terrain::{
+        block::{Block, Facing},
+        subchunk::Subchunk,
+    },
     Game,
 };
 
@@ -19,9 +22,17 @@ struct Vertex {
     position: U8Vec3,
     block_type: u8,
     normal: I8Vec3,
-    _padding: u8,
+    /// How the vertex is moved in from the edge of its block for thin blocks: [`LOWERED`],
+    /// [`PUSHED_BACK`] or 0 if it isn't.
+    inset: u8,
 }
 
+/// Moved down to the top of a thin layer, such as snow.
+const LOWERED: u8 = 1;
+/// Moved back against its face's normal, to the front of something flat against a wall such as a
+/// ladder.
+const PUSHED_BACK: u8 = 2;
+
 impl Vertex {
     const ATTRIBS: [wgpu::VertexAttribute; 2] =
         wgpu::vertex_attr_array![0 => Uint8x4, 1 => Sint8x4];
@@ -315,184 +326,253 @@ fn generate_faces_for_block(
 
     let position = u8vec3(pos.x as u8, pos.y as u8, pos.z as u8);
     let block_type = bytemuck::cast(block);
+    let first_vertex = vertices.len();
+
+    if let Some(facing) = block.facing() {
+        vertices.extend(flat_face(position, block_type, facing));
+        return;
+    }
 
     // -X
-    if neighborhood.block(pos - IVec3::X) == Block::AIR {
+    if shows_face(block, neighborhood.block(pos - IVec3::X)) {
         vertices.extend([
             Vertex {
                 position: position + u8vec3(0, 0, 0),
                 block_type,
                 normal: I8Vec3::NEG_X,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(0, 0, 1),
                 block_type,
                 normal: I8Vec3::NEG_X,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(0, 1, 1),
                 block_type,
                 normal: I8Vec3::NEG_X,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(0, 1, 0),
                 block_type,
                 normal: I8Vec3::NEG_X,
-                _padding: 0,
+                inset: 0,
             },
         ]);
     }
 
     // +X
-    if neighborhood.block(pos + IVec3::X) == Block::AIR {
+    if shows_face(block, neighborhood.block(pos + IVec3::X)) {
         vertices.extend([
             Vertex {
                 position: position + u8vec3(1, 0, 0),
                 block_type,
                 normal: I8Vec3::X,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(1, 1, 0),
                 block_type,
                 normal: I8Vec3::X,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(1, 1, 1),
                 block_type,
                 normal: I8Vec3::X,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(1, 0, 1),
                 block_type,
                 normal: I8Vec3::X,
-                _padding: 0,
+                inset: 0,
             },
         ]);
     }
 
     // -Y
-    if neighborhood.block(pos - IVec3::Y) == Block::AIR {
+    if shows_face(block, neighborhood.block(pos - IVec3::Y)) {
         vertices.extend([
             Vertex {
                 position: position + u8vec3(0, 0, 0),
                 block_type,
                 normal: I8Vec3::NEG_Y,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(1, 0, 0),
                 block_type,
                 normal: I8Vec3::NEG_Y,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(1, 0, 1),
                 block_type,
                 normal: I8Vec3::NEG_Y,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(0, 0, 1),
                 block_type,
                 normal: I8Vec3::NEG_Y,
-                _padding: 0,
+                inset: 0,
             },
         ]);
     }
 
     // +Y
-    if neighborhood.block(pos + IVec3::Y) == Block::AIR {
+    if shows_face(block, neighborhood.block(pos + IVec3::Y)) {
         vertices.extend([
             Vertex {
                 position: position + u8vec3(0, 1, 0),
                 block_type,
                 normal: I8Vec3::Y,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(0, 1, 1),
                 block_type,
                 normal: I8Vec3::Y,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(1, 1, 1),
                 block_type,
                 normal: I8Vec3::Y,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(1, 1, 0),
                 block_type,
                 normal: I8Vec3::Y,
-                _padding: 0,
+                inset: 0,
             },
         ]);
     }
 
     // -Z
-    if neighborhood.block(pos - IVec3::Z) == Block::AIR {
+    if shows_face(block, neighborhood.block(pos - IVec3::Z)) {
         vertices.extend([
             Vertex {
                 position: position + u8vec3(0, 0, 0),
                 block_type,
                 normal: I8Vec3::NEG_Z,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(0, 1, 0),
                 block_type,
                 normal: I8Vec3::NEG_Z,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(1, 1, 0),
                 block_type,
                 normal: I8Vec3::NEG_Z,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(1, 0, 0),
                 block_type,
                 normal: I8Vec3::NEG_Z,
-                _padding: 0,
+                inset: 0,
             },
         ]);
     }
 
     // +Z
-    if neighborhood.block(pos + IVec3::Z) == Block::AIR {
+    if shows_face(block, neighborhood.block(pos + IVec3::Z)) {
         vertices.extend([
             Vertex {
                 position: position + u8vec3(0, 0, 1),
                 block_type,
                 normal: I8Vec3::Z,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(1, 0, 1),
                 block_type,
                 normal: I8Vec3::Z,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(1, 1, 1),
                 block_type,
                 normal: I8Vec3::Z,
-                _padding: 0,
+                inset: 0,
             },
             Vertex {
                 position: position + u8vec3(0, 1, 1),
                 block_type,
                 normal: I8Vec3::Z,
-                _padding: 0,
+                inset: 0,
             },
         ]);
     }
+
+    if block == Block::SNOW {
+        for vertex in &mut vertices[first_vertex..] {
+            if vertex.position.y > position.y {
+                vertex.inset = LOWERED;
+            }
+        }
+    }
+}
+
+/// The front of a block that lies flat against the wall behind it, facing `facing`.
+fn flat_face(position: U8Vec3, block_type: u8, facing: Facing) -> [Vertex; 4] {
+    // Corners of the side of the block that the face is pushed back from, anticlockwise as seen
+    // from the front.
+    let (normal, corners) = match facing {
+        Facing::North => (
+            I8Vec3::NEG_Z,
+            [
+                u8vec3(0, 0, 0),
+                u8vec3(0, 1, 0),
+                u8vec3(1, 1, 0),
+                u8vec3(1, 0, 0),
+            ],
+        ),
+        Facing::South => (
+            I8Vec3::Z,
+            [
+                u8vec3(0, 0, 1),
+                u8vec3(1, 0, 1),
+                u8vec3(1, 1, 1),
+                u8vec3(0, 1, 1),
+            ],
+        ),
+        Facing::West => (
+            I8Vec3::NEG_X,
+            [
+                u8vec3(0, 0, 0),
+                u8vec3(0, 0, 1),
+                u8vec3(0, 1, 1),
+                u8vec3(0, 1, 0),
+            ],
+        ),
+        Facing::East => (
+            I8Vec3::X,
+            [
+                u8vec3(1, 0, 0),
+                u8vec3(1, 1, 0),
+                u8vec3(1, 1, 1),
+                u8vec3(1, 0, 1),
+            ],
+        ),
+    };
+    corners.map(|corner| Vertex {
+        position: position + corner,
+        block_type,
+        normal,
+        inset: PUSHED_BACK,
+    })
+}
+
+/// Whether the face of `block` that touches `neighbor` can be seen.
+fn shows_face(block: Block, neighbor: Block) -> bool {
+    !neighbor.is_solid() && neighbor != block
 }
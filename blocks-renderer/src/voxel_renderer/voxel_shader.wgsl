@@ -1,11 +1,30 @@
 // Vertex shader
 
-const CORNFLOWER_BLUE: vec4<f32> = vec4<f32>(0.4, 0.6, 0.9, 1.0);
+const WATER_COLOR: vec4<f32> = vec4<f32>(0.15, 0.3, 0.8, 1.0);
+const WATER: u32 = 4u;
+const SNOW_COLOR: vec4<f32> = vec4<f32>(0.95, 0.97, 1.0, 1.0);
+const SNOW: u32 = 5u;
+const VINES_COLOR: vec4<f32> = vec4<f32>(0.2, 0.5, 0.15, 1.0);
+// Vines and ladders facing each way.
+const FIRST_VINES: u32 = 6u;
+const LAST_VINES: u32 = 9u;
+const LADDER_COLOR: vec4<f32> = vec4<f32>(0.6, 0.45, 0.25, 1.0);
+const FIRST_LADDER: u32 = 10u;
+const LAST_LADDER: u32 = 13u;
+// How far the tops of thin layers are moved down from the top of their block, and the fronts of
+// things flat against a wall are moved back from the front of their block.
+const LAYER_DEPTH: f32 = 0.875;
+// Ways that vertices of thin blocks are moved in from the edge of their block.
+const LOWERED: i32 = 1;
+const PUSHED_BACK: i32 = 2;
 
 struct Camera {
     matrix: mat4x4<f32>,
     position: vec3<f32>,
     _aspect: f32,
+    sun_direction: vec3<f32>,
+    brightness: f32,
+    sky_color: vec3<f32>,
 }
 
 @group(0) @binding(0)
@@ -13,7 +32,7 @@ var<uniform> camera: Camera;
 
 struct VertexInput {
     @location(0) position_and_block_type: vec4<u32>,
-    @location(1) normal_and_padding: vec4<i32>,
+    @location(1) normal_and_inset: vec4<i32>,
     @location(2) subchunk_position: vec3<i32>,
 };
 
@@ -29,9 +48,15 @@ struct VertexOutput {
 fn vs_main(
     model: VertexInput,
 ) -> VertexOutput {
-    let position = vec3<f32>(model.position_and_block_type.xyz);
+    var position = vec3<f32>(model.position_and_block_type.xyz);
     let block_type = model.position_and_block_type.w;
-    let normal = model.normal_and_padding.xyz;
+    let normal = model.normal_and_inset.xyz;
+    if (model.normal_and_inset.w == LOWERED) {
+        position.y -= LAYER_DEPTH;
+    }
+    if (model.normal_and_inset.w == PUSHED_BACK) {
+        position -= LAYER_DEPTH * vec3<f32>(normal);
+    }
     let subchunk_position = vec3<f32>(model.subchunk_position);
     var out: VertexOutput;
 
@@ -39,8 +64,8 @@ fn vs_main(
     out.clip_position = camera.matrix * vec4(world_position, 1.0);
     out.relative_position = world_position - camera.position;
 
-    let light_direction = normalize(vec3<f32>(1.0, 3.0, -2.0));
-    let value = 0.5 + 0.5 * max(0.0, dot(vec3<f32>(normal), light_direction));
+    let sunlight = max(0.0, dot(vec3<f32>(normal), camera.sun_direction));
+    let value = camera.brightness * (0.5 + 0.5 * sunlight);
     out.light_intensity = value;
     out.block_type = block_type;
 
@@ -82,11 +107,24 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let texture_position = vec2(f32(in.block_type), 0.0);
     let atlas_coords = (texture_coords + texture_position) / vec2(4.0, 1.0);
 
-    let sample = textureSample(t_diffuse, s_diffuse, atlas_coords);
+    var sample = textureSample(t_diffuse, s_diffuse, atlas_coords);
+    if (in.block_type == WATER) {
+        // Water doesn't have a texture in the atlas yet.
+        sample = WATER_COLOR;
+    }
+    if (in.block_type == SNOW) {
+        sample = SNOW_COLOR;
+    }
+    if (in.block_type >= FIRST_VINES && in.block_type <= LAST_VINES) {
+        sample = VINES_COLOR;
+    }
+    if (in.block_type >= FIRST_LADDER && in.block_type <= LAST_LADDER) {
+        sample = LADDER_COLOR;
+    }
     let world_color = darken(sample, in.light_intensity);
 
     let too_far = clamp((length(in.relative_position) - 40.0) / 8.0, 0.0, 1.0);
-    return world_color + too_far * (CORNFLOWER_BLUE - world_color);
+    return world_color + too_far * (vec4(camera.sky_color, 1.0) - world_color);
 }
 
 fn srgb_to_linear(color: u32) -> vec4<f32> {
@@ -0,0 +1,277 @@
+use std::{f32::consts::TAU, mem};
+
+use glam::{ivec3, vec2, vec3, Vec2, Vec3};
+use wgpu::util::DeviceExt;
+
+use blocks_game::{
+    terrain::generation::climate::{self, Precipitation},
+    Game,
+};
+
+use crate::texture;
+
+/// How many blocks around the camera horizontally and vertically particles fall in.
+const RADIUS: i32 = 10;
+const HEIGHT: f32 = 24.0;
+const PARTICLES_PER_COLUMN: u32 = 2;
+/// How many different random numbers each particle uses.
+const PARTICLE_SALTS: u32 = 5;
+const MAX_PARTICLES: usize =
+    ((2 * RADIUS + 1) * (2 * RADIUS + 1)) as usize * PARTICLES_PER_COLUMN as usize + 1;
+/// Blocks per second that rain and snow fall at.
+const RAIN_SPEED: f32 = 12.0;
+const SNOW_SPEED: f32 = 1.5;
+/// How far snowflakes drift from side to side.
+const SNOW_SWAY: f32 = 0.3;
+const RAIN_SIZE: Vec2 = vec2(0.03, 0.6);
+const SNOW_SIZE: Vec2 = vec2(0.08, 0.08);
+const LIGHTNING_SIZE: Vec2 = vec2(0.4, 128.0);
+const RAIN_COLOR: [f32; 4] = [0.55, 0.65, 0.9, 0.5];
+const SNOW_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.9];
+const LIGHTNING_COLOR: [f32; 4] = [0.9, 0.9, 1.0, 0.9];
+
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct Vertex {
+    corner: Vec2,
+}
+
+impl Vertex {
+    const ATTRIBS: [wgpu::VertexAttribute; 1] = wgpu::vertex_attr_array![0 => Float32x2];
+
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+/// A quad standing upright from its bottom-center.
+const QUAD: [Vertex; 6] = [
+    Vertex {
+        corner: vec2(-0.5, 0.0),
+    },
+    Vertex {
+        corner: vec2(0.5, 0.0),
+    },
+    Vertex {
+        corner: vec2(0.5, 1.0),
+    },
+    Vertex {
+        corner: vec2(-0.5, 0.0),
+    },
+    Vertex {
+        corner: vec2(0.5, 1.0),
+    },
+    Vertex {
+        corner: vec2(-0.5, 1.0),
+    },
+];
+
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct Instance {
+    position: Vec3,
+    width: f32,
+    color: [f32; 4],
+    height: f32,
+}
+
+impl Instance {
+    const ATTRIBS: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![
+        1 => Float32x3, 2 => Float32, 3 => Float32x4, 4 => Float32
+    ];
+
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+
+    fn new(position: Vec3, size: Vec2, color: [f32; 4]) -> Self {
+        Self {
+            position,
+            width: size.x,
+            color,
+            height: size.y,
+        }
+    }
+}
+
+/// A number from 0 to 1 that is always the same for the same column and particle.
+fn column_noise(x: i32, z: i32, particle: u32) -> f32 {
+    let mut hash = (x as u32).wrapping_mul(0x8DA6_B343)
+        ^ (z as u32).wrapping_mul(0xD816_3841)
+        ^ particle.wrapping_mul(0xCB1A_B31F);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x2C1B_3C6D);
+    hash ^= hash >> 12;
+    (hash >> 8) as f32 / (1 << 24) as f32
+}
+
+/// Draws falling rain and snow around the camera, and bolts of lightning.
+pub struct WeatherRenderer {
+    render_pipeline: wgpu::RenderPipeline,
+    vertex_buffer: wgpu::Buffer,
+    instance_buffer: wgpu::Buffer,
+    instance_count: u32,
+    /// Seconds that particles have been falling for.
+    elapsed: f32,
+}
+
+impl WeatherRenderer {
+    pub fn new(
+        device: &wgpu::Device,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        color_target_format: wgpu::TextureFormat,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("weather_shader.wgsl"));
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Weather Render Pipeline Layout"),
+                bind_group_layouts: &[camera_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Weather Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc(), Instance::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: color_target_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            // Particles are see-through, so they're hidden behind blocks but don't hide each
+            // other.
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: texture::Texture::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Weather Vertex Buffer"),
+            contents: bytemuck::cast_slice(&QUAD),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Weather Instance Buffer"),
+            size: (MAX_PARTICLES * mem::size_of::<Instance>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            render_pipeline,
+            vertex_buffer,
+            instance_buffer,
+            instance_count: 0,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, queue: &wgpu::Queue, game: &Game, delta_time: f32) {
+        self.elapsed += delta_time;
+
+        let mut instances = Vec::new();
+        if game.weather.is_precipitating() {
+            self.add_particles(game, &mut instances);
+        }
+        if let Some(lightning) = game.weather.lightning {
+            instances.push(Instance::new(
+                lightning.position,
+                LIGHTNING_SIZE,
+                LIGHTNING_COLOR,
+            ));
+        }
+
+        queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
+        self.instance_count = instances.len() as u32;
+    }
+
+    /// Adds rain or snow to every column around the camera that it's falling in, stopping at the
+    /// ground.
+    fn add_particles(&self, game: &Game, instances: &mut Vec<Instance>) {
+        let camera = game.interpolated_head_position();
+        let bottom = camera.y - HEIGHT / 2.0;
+
+        for x in camera.x as i32 - RADIUS..=camera.x as i32 + RADIUS {
+            for z in camera.z as i32 - RADIUS..=camera.z as i32 + RADIUS {
+                let Some(surface) = game.terrain.surface_height(x, z) else {
+                    continue;
+                };
+                let ground = ivec3(x, surface + 1, z);
+                let (speed, sway, size, color) =
+                    match climate::precipitation(game.terrain.seed, ground) {
+                        Precipitation::None => continue,
+                        Precipitation::Rain => (RAIN_SPEED, 0.0, RAIN_SIZE, RAIN_COLOR),
+                        Precipitation::Snow => (SNOW_SPEED, SNOW_SWAY, SNOW_SIZE, SNOW_COLOR),
+                    };
+
+                for particle in 0..PARTICLES_PER_COLUMN {
+                    let noise = |salt| column_noise(x, z, PARTICLE_SALTS * particle + salt);
+                    if noise(0) > game.weather.intensity() {
+                        continue;
+                    }
+                    let fallen = self.elapsed * speed - noise(1) * HEIGHT;
+                    let y = bottom + (-fallen - bottom).rem_euclid(HEIGHT);
+                    if y < ground.y as f32 {
+                        continue;
+                    }
+
+                    let drift = sway * (self.elapsed + noise(4) * TAU).sin();
+                    let position = vec3(x as f32 + noise(2) + drift, y, z as f32 + noise(3));
+                    instances.push(Instance::new(position, size, color));
+                }
+            }
+        }
+    }
+
+    pub fn render(&self, render_pass: &mut wgpu::RenderPass, camera_bind_group: &wgpu::BindGroup) {
+        if self.instance_count == 0 {
+            return;
+        }
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, camera_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        render_pass.draw(0..QUAD.len() as u32, 0..self.instance_count);
+    }
+}
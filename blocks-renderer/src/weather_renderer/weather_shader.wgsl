@@ -0,0 +1,58 @@
+// Vertex shader
+
+struct Camera {
+    matrix: mat4x4<f32>,
+    position: vec3<f32>,
+    _aspect: f32,
+    sun_direction: vec3<f32>,
+    brightness: f32,
+    sky_color: vec3<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct VertexInput {
+    @location(0) corner: vec2<f32>,
+};
+
+struct InstanceInput {
+    @location(1) position: vec3<f32>,
+    @location(2) width: f32,
+    @location(3) color: vec4<f32>,
+    @location(4) height: f32,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+    instance: InstanceInput,
+) -> VertexOutput {
+    // Turn the quad around its vertical axis to face the camera.
+    let to_camera = camera.position.xz - instance.position.xz;
+    var right = vec3<f32>(1.0, 0.0, 0.0);
+    if length(to_camera) > 0.001 {
+        let facing = normalize(to_camera);
+        right = vec3<f32>(facing.y, 0.0, -facing.x);
+    }
+    let world_position = instance.position
+        + right * model.corner.x * instance.width
+        + vec3<f32>(0.0, model.corner.y * instance.height, 0.0);
+
+    var out: VertexOutput;
+    out.clip_position = camera.matrix * vec4(world_position, 1.0);
+    out.color = vec4(instance.color.rgb * camera.brightness, instance.color.a);
+    return out;
+}
+
+// Fragment shader
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
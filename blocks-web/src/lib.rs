@@ -1,7 +1,15 @@
 use wasm_bindgen::prelude::*;
 use winit::{event_loop::EventLoop, window::WindowBuilder};
 
-use blocks_renderer::State;
+use blocks_game::{crafting::Recipes, save::SaveData, TICK_RATES};
+use blocks_renderer::{input::Bindings, State};
+
+/// The local storage key the world's settings are saved under.
+const SAVE_KEY: &str = "blocks-world";
+/// The local storage key custom controls are read from.
+const BINDINGS_KEY: &str = "blocks-bindings";
+/// The local storage key custom crafting recipes are read from.
+const RECIPES_KEY: &str = "blocks-recipes";
 
 #[wasm_bindgen(start)]
 async fn run() {
@@ -31,10 +39,83 @@ async fn run() {
     let h = win.inner_height().unwrap().as_f64().unwrap() as u32;
     state.resize(winit::dpi::PhysicalSize::new(w, h));
     state.manual_size = true;
+    state.set_bindings(load_bindings());
+    state.game_mut().load(&load_world());
+    state.game_mut().recipes = load_recipes();
+    state.set_save_handler(save_world);
+    if let Some(tick_rate) = tick_rate_from_url() {
+        state.game_mut().set_tick_rate(tick_rate);
+    }
 
     state.run(event_loop).unwrap();
 }
 
+/// Reads the game ticks per second from a `tick_rate` parameter in the page's URL, such as
+/// `?tick_rate=30`.
+fn tick_rate_from_url() -> Option<f32> {
+    let search = web_sys::window()?.location().search().ok()?;
+    let value = search
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|parameter| parameter.strip_prefix("tick_rate="))?;
+    match value.parse() {
+        Ok(rate) if TICK_RATES.contains(&rate) => Some(rate),
+        _ => {
+            log::warn!("Ignoring tick_rate={value}, which isn't a number within {TICK_RATES:?}");
+            None
+        }
+    }
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Reads the controls from the browser's local storage, in the format of a bindings file, using
+/// the defaults if there aren't any there.
+fn load_bindings() -> Bindings {
+    let Some(source) = local_storage().and_then(|storage| storage.get_item(BINDINGS_KEY).ok()?)
+    else {
+        return Bindings::default();
+    };
+    Bindings::parse(&source).unwrap_or_else(|error| {
+        log::warn!("Ignoring custom bindings, {error}");
+        Bindings::default()
+    })
+}
+
+/// Reads crafting recipes from the browser's local storage, in the format of a recipes file,
+/// using the built-in ones if there aren't any there.
+fn load_recipes() -> Recipes {
+    let Some(source) = local_storage().and_then(|storage| storage.get_item(RECIPES_KEY).ok()?)
+    else {
+        return Recipes::builtin();
+    };
+    Recipes::parse(&source).unwrap_or_else(|error| {
+        log::warn!("Ignoring custom recipes, {error}");
+        Recipes::builtin()
+    })
+}
+
+/// Reads the world's settings from the browser's local storage, if they were saved there.
+fn load_world() -> SaveData {
+    let Some(source) = local_storage().and_then(|storage| storage.get_item(SAVE_KEY).ok()?) else {
+        return SaveData::default();
+    };
+    SaveData::parse(&source).unwrap_or_else(|error| {
+        log::warn!("Ignoring saved world, {error}");
+        SaveData::default()
+    })
+}
+
+fn save_world(data: &SaveData) {
+    let saved =
+        local_storage().and_then(|storage| storage.set_item(SAVE_KEY, &data.to_string()).ok());
+    if saved.is_none() {
+        log::warn!("Couldn't save the world to local storage");
+    }
+}
+
 struct Clock {
     performance: web_sys::Performance,
 }
@@ -1,17 +1,132 @@
+use std::{env, fs, process};
+
 use winit::{event_loop::EventLoop, window::WindowBuilder};
 
-use blocks_renderer::State;
+use blocks_game::{
+    crafting::{Recipes, BUILTIN_RECIPES},
+    save::SaveData,
+    TICK_RATES,
+};
+use blocks_renderer::{
+    input::{Bindings, DEFAULT_BINDINGS},
+    State,
+};
+
+/// Where the controls are read from, relative to the working directory.
+const BINDINGS_PATH: &str = "bindings.txt";
+/// Where the crafting recipes are read from, relative to the working directory.
+const RECIPES_PATH: &str = "recipes.txt";
+/// Where the world's settings are saved, relative to the working directory.
+const SAVE_PATH: &str = "world.txt";
+
+const USAGE: &str = "\
+Usage: blocks [OPTIONS]
+
+Options:
+  --tick-rate <TICKS>   Game ticks per second, from 1 to 1000 [default: 60]";
 
 #[pollster::main]
 async fn main() {
+    let tick_rate = match parse_tick_rate(env::args().skip(1)) {
+        Ok(tick_rate) => tick_rate,
+        Err(message) => {
+            eprintln!("{message}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+
     let event_loop = EventLoop::new().unwrap();
     let window = WindowBuilder::new().build(&event_loop).unwrap();
 
     let mut state = State::new(&window, wgpu::Backends::GL, Clock).await;
+    state.set_bindings(load_bindings());
+    state.game_mut().recipes = load_recipes();
+    state.game_mut().load(&load_world());
+    state.set_save_handler(save_world);
+    if let Some(tick_rate) = tick_rate {
+        state.game_mut().set_tick_rate(tick_rate);
+    }
 
     state.run(event_loop).unwrap();
 }
 
+/// Reads the `--tick-rate` option, if it was given.
+fn parse_tick_rate(mut args: impl Iterator<Item = String>) -> Result<Option<f32>, String> {
+    let mut tick_rate = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tick-rate" => {
+                let value = args.next().ok_or("Missing value for --tick-rate")?;
+                match value.trim().parse() {
+                    Ok(rate) if TICK_RATES.contains(&rate) => tick_rate = Some(rate),
+                    _ => {
+                        return Err(format!(
+                            "Expected a number from {} to {} but got {value:?}",
+                            TICK_RATES.start(),
+                            TICK_RATES.end()
+                        ))
+                    }
+                }
+            }
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                process::exit(0);
+            }
+            _ => return Err(format!("Unexpected argument {arg:?}")),
+        }
+    }
+    Ok(tick_rate)
+}
+
+/// Reads the controls from the bindings file, first writing out the defaults if there isn't one
+/// so that they can be edited.
+fn load_bindings() -> Bindings {
+    let Ok(source) = fs::read_to_string(BINDINGS_PATH) else {
+        if let Err(error) = fs::write(BINDINGS_PATH, DEFAULT_BINDINGS) {
+            eprintln!("Couldn't write {BINDINGS_PATH}: {error}");
+        }
+        return Bindings::default();
+    };
+    Bindings::parse(&source).unwrap_or_else(|error| {
+        eprintln!("Ignoring {BINDINGS_PATH}, {error}");
+        Bindings::default()
+    })
+}
+
+/// Reads the crafting recipes from the recipes file, first writing out the built-in ones if
+/// there isn't one so that they can be edited.
+fn load_recipes() -> Recipes {
+    let Ok(source) = fs::read_to_string(RECIPES_PATH) else {
+        if let Err(error) = fs::write(RECIPES_PATH, BUILTIN_RECIPES) {
+            eprintln!("Couldn't write {RECIPES_PATH}: {error}");
+        }
+        return Recipes::builtin();
+    };
+    Recipes::parse(&source).unwrap_or_else(|error| {
+        eprintln!("Ignoring {RECIPES_PATH}, {error}");
+        Recipes::builtin()
+    })
+}
+
+/// Reads the world's settings, first writing out the defaults if there isn't a settings file so
+/// that the seed can be edited.
+fn load_world() -> SaveData {
+    let Ok(source) = fs::read_to_string(SAVE_PATH) else {
+        save_world(&SaveData::default());
+        return SaveData::default();
+    };
+    SaveData::parse(&source).unwrap_or_else(|error| {
+        eprintln!("Ignoring {SAVE_PATH}, {error}");
+        SaveData::default()
+    })
+}
+
+fn save_world(data: &SaveData) {
+    if let Err(error) = fs::write(SAVE_PATH, data.to_string()) {
+        eprintln!("Couldn't write {SAVE_PATH}: {error}");
+    }
+}
+
 struct Clock;
 
 impl blocks_renderer::clock::Clock for Clock {